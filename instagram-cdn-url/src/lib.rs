@@ -1,6 +1,6 @@
 use core::{char::from_digit, fmt};
 
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, Utc};
 use url::{ParseError, Url};
 
 pub struct CdnUrl {
@@ -67,7 +67,7 @@ pub fn oe_string_to_datetime(oe: impl AsRef<str>) -> Result<DateTime<Utc>, Strin
     let oe_timestamp = u32::from_str_radix(oe.as_ref(), 16).map_err(|_| "invalid".to_owned())?;
 
     let oe_datetime =
-        DateTime::from_utc(NaiveDateTime::from_timestamp(oe_timestamp as i64, 0), Utc);
+        DateTime::from_timestamp(oe_timestamp as i64, 0).ok_or_else(|| "invalid".to_owned())?;
 
     Ok(oe_datetime)
 }
@@ -93,26 +93,26 @@ mod tests {
 
     use std::error;
 
-    use chrono::{NaiveDate, NaiveTime};
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 
     #[test]
     fn test_oe_string_and_datetime_converter() -> Result<(), Box<dyn error::Error>> {
         assert_eq!(
             oe_string_to_datetime("600DBA0C")?,
-            DateTime::<Utc>::from_utc(
+            DateTime::<Utc>::from_naive_utc_and_offset(
                 NaiveDateTime::new(
-                    NaiveDate::from_ymd(2021, 1, 24),
-                    NaiveTime::from_hms(18, 18, 52)
+                    NaiveDate::from_ymd_opt(2021, 1, 24).unwrap(),
+                    NaiveTime::from_hms_opt(18, 18, 52).unwrap()
                 ),
                 Utc
             )
         );
 
         assert_eq!(
-            oe_datetime_to_string(DateTime::<Utc>::from_utc(
+            oe_datetime_to_string(DateTime::<Utc>::from_naive_utc_and_offset(
                 NaiveDateTime::new(
-                    NaiveDate::from_ymd(2021, 1, 24),
-                    NaiveTime::from_hms(18, 18, 52)
+                    NaiveDate::from_ymd_opt(2021, 1, 24).unwrap(),
+                    NaiveTime::from_hms_opt(18, 18, 52).unwrap()
                 ),
                 Utc
             )),
@@ -127,10 +127,10 @@ mod tests {
         let cdn_url = CdnUrl::parse( "https://scontent-lax3-1.cdninstagram.com/v/t51.2885-19/s150x150/14718046_215742295528430_4651559330867314688_a.jpg?_nc_ht=scontent-lax3-1.cdninstagram.com&_nc_ohc=n76LD7OkqcEAX_rFqpg&tp=1&oh=b68eb21889f4d6406bea1db175f16b3b&oe=600DBA0C")?;
 
         if Utc::now()
-            > DateTime::<Utc>::from_utc(
+            > DateTime::<Utc>::from_naive_utc_and_offset(
                 NaiveDateTime::new(
-                    NaiveDate::from_ymd(2021, 1, 24),
-                    NaiveTime::from_hms(18, 18, 52),
+                    NaiveDate::from_ymd_opt(2021, 1, 24).unwrap(),
+                    NaiveTime::from_hms_opt(18, 18, 52).unwrap(),
                 ),
                 Utc,
             )
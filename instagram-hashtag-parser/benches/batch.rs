@@ -0,0 +1,61 @@
+#[macro_use]
+extern crate criterion;
+
+use criterion::{Criterion, Throughput};
+use csv::Reader;
+use instagram_hashtag_parser::{extract_batch, extract_spans_batch, Backend};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::fs;
+
+static CAPTIONS: Lazy<Vec<String>> = Lazy::new(|| {
+    // tests/files/captions.csv is not checked in
+    let data = fs::read_to_string("tests/files/captions.csv")
+        .or_else(|_| fs::read_to_string("tests/files/captions.csv.example"))
+        .unwrap();
+    let mut rdr = Reader::from_reader(data.as_bytes());
+    #[derive(Deserialize)]
+    struct Row {
+        caption: String,
+    }
+
+    let mut captions = vec![];
+    for row in rdr.deserialize::<Row>().flatten() {
+        captions.push(row.caption);
+    }
+    captions
+});
+
+fn batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batch");
+    group.throughput(Throughput::Elements(CAPTIONS.len() as u64));
+
+    group.bench_function("single_threaded", |b| {
+        b.iter(|| {
+            CAPTIONS
+                .iter()
+                .map(|x| Backend::default().hashtags(x))
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.bench_function("parallel", |b| b.iter(|| extract_batch(&CAPTIONS)));
+
+    group.bench_function("spans_single_threaded", |b| {
+        b.iter(|| {
+            CAPTIONS
+                .iter()
+                .map(|x| Backend::default().hashtag_spans(x))
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.bench_function("spans_parallel", |b| {
+        b.iter(|| extract_spans_batch(&CAPTIONS))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, batch);
+criterion_main!(benches);
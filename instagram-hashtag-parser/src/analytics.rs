@@ -0,0 +1,367 @@
+//! Tag counts, top-N tags and co-occurrence of tags, keyed by [`normalize_hashtag`](crate::normalize_hashtag).
+
+use std::collections::{BTreeSet, HashMap};
+#[cfg(feature = "with-csv")]
+use std::io;
+
+#[cfg(feature = "with-serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Backend;
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+pub struct TagCount {
+    pub tag: String,
+    /// Occurrences across all captions.
+    pub count: u64,
+    /// Captions containing the tag at least once.
+    pub posts: u64,
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+pub struct TagPair {
+    /// `tag_a < tag_b`
+    pub tag_a: String,
+    pub tag_b: String,
+    /// Captions containing both tags.
+    pub posts: u64,
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+pub struct PostTags {
+    pub index: usize,
+    /// Sorted and deduplicated.
+    pub tags: Vec<String>,
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+pub struct Report {
+    pub posts: usize,
+    pub frequencies: Vec<TagCount>,
+    pub co_occurrences: Vec<TagPair>,
+}
+
+//
+#[derive(Debug, Clone)]
+pub struct Analytics {
+    backend: Backend,
+    keep_post_tags: bool,
+    posts: usize,
+    counts: HashMap<String, (u64, u64)>,
+    pairs: HashMap<(String, String), u64>,
+    post_tags: Vec<PostTags>,
+}
+
+impl Default for Analytics {
+    fn default() -> Self {
+        Self::with_backend(Backend::default())
+    }
+}
+
+impl Analytics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_backend(backend: Backend) -> Self {
+        Self {
+            backend,
+            keep_post_tags: false,
+            posts: 0,
+            counts: HashMap::new(),
+            pairs: HashMap::new(),
+            post_tags: vec![],
+        }
+    }
+
+    /// Keeps the tag set of every caption, see [`Analytics::post_tags`].
+    pub fn keep_post_tags(mut self) -> Self {
+        self.keep_post_tags = true;
+        self
+    }
+
+    pub fn from_captions<I, S>(captions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut analytics = Self::default();
+        analytics.extend(captions);
+        analytics
+    }
+
+    pub fn add_caption(&mut self, caption: &str) {
+        let tags = self.backend.hashtags(caption);
+        self.add_tags(tags);
+    }
+
+    /// Adds the tags of one caption, e.g. [`Record::hashtags`](crate::stream::Record::hashtags).
+    pub fn add_tags<I, S>(&mut self, tags: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut set = BTreeSet::new();
+        for tag in tags {
+            let key = crate::normalize_hashtag(tag);
+            if key.is_empty() {
+                continue;
+            }
+            let (count, posts) = self.counts.entry(key.to_owned()).or_default();
+            *count += 1;
+            if set.insert(key) {
+                *posts += 1;
+            }
+        }
+
+        let tags = set.into_iter().collect::<Vec<_>>();
+        for (i, tag_a) in tags.iter().enumerate() {
+            for tag_b in &tags[i + 1..] {
+                *self
+                    .pairs
+                    .entry((tag_a.to_owned(), tag_b.to_owned()))
+                    .or_default() += 1;
+            }
+        }
+
+        if self.keep_post_tags {
+            self.post_tags.push(PostTags {
+                index: self.posts,
+                tags,
+            });
+        }
+        self.posts += 1;
+    }
+
+    pub fn posts(&self) -> usize {
+        self.posts
+    }
+
+    /// Sorted by `count` desc, then by `tag`.
+    pub fn frequencies(&self) -> Vec<TagCount> {
+        let mut frequencies = self
+            .counts
+            .iter()
+            .map(|(tag, (count, posts))| TagCount {
+                tag: tag.to_owned(),
+                count: *count,
+                posts: *posts,
+            })
+            .collect::<Vec<_>>();
+        frequencies.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+        frequencies
+    }
+
+    pub fn top(&self, n: usize) -> Vec<TagCount> {
+        let mut frequencies = self.frequencies();
+        frequencies.truncate(n);
+        frequencies
+    }
+
+    /// Sorted by `posts` desc, then by `tag_a` and `tag_b`.
+    pub fn co_occurrences(&self) -> Vec<TagPair> {
+        let mut pairs = self
+            .pairs
+            .iter()
+            .map(|((tag_a, tag_b), posts)| TagPair {
+                tag_a: tag_a.to_owned(),
+                tag_b: tag_b.to_owned(),
+                posts: *posts,
+            })
+            .collect::<Vec<_>>();
+        pairs.sort_by(|a, b| {
+            b.posts
+                .cmp(&a.posts)
+                .then_with(|| a.tag_a.cmp(&b.tag_a))
+                .then_with(|| a.tag_b.cmp(&b.tag_b))
+        });
+        pairs
+    }
+
+    /// Co-occurrence count of two tags, in either order.
+    pub fn co_occurrence(&self, tag_a: &str, tag_b: &str) -> u64 {
+        let tag_a = crate::normalize_hashtag(tag_a);
+        let tag_b = crate::normalize_hashtag(tag_b);
+        let key = if tag_a <= tag_b {
+            (tag_a, tag_b)
+        } else {
+            (tag_b, tag_a)
+        };
+        self.pairs.get(&key).copied().unwrap_or(0)
+    }
+
+    /// Empty unless [`Analytics::keep_post_tags`] is set.
+    pub fn post_tags(&self) -> &[PostTags] {
+        &self.post_tags
+    }
+
+    pub fn report(&self) -> Report {
+        Report {
+            posts: self.posts,
+            frequencies: self.frequencies(),
+            co_occurrences: self.co_occurrences(),
+        }
+    }
+
+    /// Writes `tag,count,posts` rows with a header.
+    #[cfg(feature = "with-csv")]
+    pub fn write_frequencies_csv<W: io::Write>(&self, w: W) -> Result<(), csv::Error> {
+        let mut wtr = csv::Writer::from_writer(w);
+        wtr.write_record(["tag", "count", "posts"])?;
+        for x in self.frequencies() {
+            wtr.write_record([x.tag, x.count.to_string(), x.posts.to_string()])?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+
+    /// Writes `tag_a,tag_b,posts` rows with a header.
+    #[cfg(feature = "with-csv")]
+    pub fn write_co_occurrences_csv<W: io::Write>(&self, w: W) -> Result<(), csv::Error> {
+        let mut wtr = csv::Writer::from_writer(w);
+        wtr.write_record(["tag_a", "tag_b", "posts"])?;
+        for x in self.co_occurrences() {
+            wtr.write_record([x.tag_a, x.tag_b, x.posts.to_string()])?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for Analytics {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, captions: I) {
+        for caption in captions {
+            self.add_caption(caption.as_ref());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analytics() -> Analytics {
+        let mut analytics = Analytics::new().keep_post_tags();
+        analytics.extend([
+            "#rust is #awesome #Rust",
+            "#RUST and #wasm",
+            "no tags",
+            "#awesome #wasm #rust",
+        ]);
+        analytics
+    }
+
+    #[test]
+    fn test_frequencies() {
+        let analytics = analytics();
+        assert_eq!(analytics.posts(), 4);
+        assert_eq!(
+            analytics.frequencies(),
+            vec![
+                TagCount {
+                    tag: "rust".to_owned(),
+                    count: 4,
+                    posts: 3
+                },
+                TagCount {
+                    tag: "awesome".to_owned(),
+                    count: 2,
+                    posts: 2
+                },
+                TagCount {
+                    tag: "wasm".to_owned(),
+                    count: 2,
+                    posts: 2
+                },
+            ]
+        );
+        assert_eq!(
+            analytics
+                .top(1)
+                .into_iter()
+                .map(|x| x.tag)
+                .collect::<Vec<_>>(),
+            vec!["rust".to_owned()]
+        );
+    }
+
+    #[test]
+    fn test_co_occurrences() {
+        let analytics = analytics();
+        assert_eq!(
+            analytics
+                .co_occurrences()
+                .into_iter()
+                .map(|x| (x.tag_a, x.tag_b, x.posts))
+                .collect::<Vec<_>>(),
+            vec![
+                ("awesome".to_owned(), "rust".to_owned(), 2),
+                ("rust".to_owned(), "wasm".to_owned(), 2),
+                ("awesome".to_owned(), "wasm".to_owned(), 1),
+            ]
+        );
+        assert_eq!(analytics.co_occurrence("Wasm", "rust"), 2);
+        assert_eq!(analytics.co_occurrence("rust", "foo"), 0);
+    }
+
+    #[test]
+    fn test_post_tags() {
+        let analytics = analytics();
+        assert_eq!(
+            analytics.post_tags()[0],
+            PostTags {
+                index: 0,
+                tags: vec!["awesome".to_owned(), "rust".to_owned()]
+            }
+        );
+        assert_eq!(analytics.post_tags()[2].tags, Vec::<String>::new());
+
+        assert!(Analytics::from_captions(["#foo"]).post_tags().is_empty());
+    }
+
+    #[cfg(feature = "with-csv")]
+    #[test]
+    fn test_write_csv() -> Result<(), csv::Error> {
+        let analytics = analytics();
+
+        let mut buf = vec![];
+        analytics.write_frequencies_csv(&mut buf)?;
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "tag,count,posts\nrust,4,3\nawesome,2,2\nwasm,2,2\n"
+        );
+
+        let mut buf = vec![];
+        analytics.write_co_occurrences_csv(&mut buf)?;
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "tag_a,tag_b,posts\nawesome,rust,2\nrust,wasm,2\nawesome,wasm,1\n"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-json")]
+    #[test]
+    fn test_serialize_json() -> Result<(), serde_json::Error> {
+        let report = Analytics::from_captions(["#foo #bar"]).report();
+        assert_eq!(
+            serde_json::to_string(&report)?,
+            r#"{"posts":1,"frequencies":[{"tag":"bar","count":1,"posts":1},{"tag":"foo","count":1,"posts":1}],"co_occurrences":[{"tag_a":"bar","tag_b":"foo","posts":1}]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Report>(&serde_json::to_string(&report)?)?,
+            report
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use rayon::prelude::*;
+
+use crate::{Backend, HashtagSpan};
+
+/// Extracts the hashtags of every caption in parallel, the output keeps the order of `captions`.
+pub fn extract_batch<S: AsRef<str> + Sync>(captions: &[S]) -> Vec<Vec<String>> {
+    extract_batch_with_backend(captions, Backend::default())
+}
+
+pub fn extract_batch_with_backend<S: AsRef<str> + Sync>(
+    captions: &[S],
+    backend: Backend,
+) -> Vec<Vec<String>> {
+    captions
+        .par_iter()
+        .map(|caption| backend.hashtags(caption.as_ref()))
+        .collect()
+}
+
+/// Extracts the hashtag spans of every caption in parallel, the output keeps the order of `captions`.
+///
+/// Each worker thread reuses one char offset table across its captions.
+pub fn extract_spans_batch<S: AsRef<str> + Sync>(captions: &[S]) -> Vec<Vec<HashtagSpan>> {
+    extract_spans_batch_with_backend(captions, Backend::default())
+}
+
+pub fn extract_spans_batch_with_backend<S: AsRef<str> + Sync>(
+    captions: &[S],
+    backend: Backend,
+) -> Vec<Vec<HashtagSpan>> {
+    captions
+        .par_iter()
+        .map_init(Vec::new, |offsets, caption| {
+            backend.hashtag_spans_in(caption.as_ref(), offsets)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_batch() {
+        let captions = (0..1000)
+            .map(|i| format!("#tag{} is #awesome", i))
+            .collect::<Vec<_>>();
+
+        let hashtags = extract_batch(&captions);
+        assert_eq!(hashtags.len(), captions.len());
+        for (i, tags) in hashtags.iter().enumerate() {
+            assert_eq!(tags, &vec![format!("tag{}", i), "awesome".to_owned()]);
+        }
+
+        assert_eq!(
+            extract_batch(&captions),
+            captions
+                .iter()
+                .map(|x| Backend::default().hashtags(x))
+                .collect::<Vec<_>>()
+        );
+
+        assert!(extract_batch::<String>(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_extract_spans_batch() {
+        let captions = (0..1000)
+            .map(|i| format!("{}#我的 #tag{}", "é".repeat(i % 7), i))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            extract_spans_batch(&captions),
+            captions
+                .iter()
+                .map(|x| Backend::default().hashtag_spans(x))
+                .collect::<Vec<_>>()
+        );
+
+        assert!(extract_spans_batch::<String>(&[]).is_empty());
+    }
+}
@@ -0,0 +1,96 @@
+//! Comments, where a reply starts with `@parent_user`.
+
+use crate::{mentions, Backend, HashtagSpan, MentionSpan};
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedComment {
+    /// The `@username` the comment starts with, after leading whitespace.
+    pub reply_to: Option<MentionSpan>,
+    /// Mentions after `reply_to`.
+    pub mentions: Vec<MentionSpan>,
+    pub hashtags: Vec<HashtagSpan>,
+    /// At least one hashtag and no other words, `reply_to`, emoji and punctuation aside.
+    pub tags_only: bool,
+}
+
+pub fn parse_comment(text: &str) -> ParsedComment {
+    parse_comment_with(text, Backend::default())
+}
+
+pub fn parse_comment_with(text: &str, backend: Backend) -> ParsedComment {
+    let hashtags = backend.hashtag_spans(text);
+
+    let mut mentions = mentions(text);
+    let leading = text.len() - text.trim_start().len();
+    let reply_to = if mentions.first().map(|x| x.span.start) == Some(leading) {
+        Some(mentions.remove(0))
+    } else {
+        None
+    };
+
+    let mut rest = text.to_owned();
+    for span in hashtags
+        .iter()
+        .map(|x| &x.span)
+        .chain(reply_to.iter().map(|x| &x.span))
+    {
+        rest.replace_range(span.to_owned(), &" ".repeat(span.len()));
+    }
+    let tags_only = !hashtags.is_empty() && !rest.chars().any(char::is_alphanumeric);
+
+    ParsedComment {
+        reply_to,
+        mentions,
+        hashtags,
+        tags_only,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_comment() {
+        let comment = parse_comment(" @foo thanks @bar for #rust");
+        assert_eq!(
+            comment.reply_to,
+            Some(MentionSpan {
+                username: "foo".to_owned(),
+                span: 1..5
+            })
+        );
+        assert_eq!(
+            comment
+                .mentions
+                .iter()
+                .map(|x| x.username.as_str())
+                .collect::<Vec<_>>(),
+            vec!["bar"]
+        );
+        assert_eq!(
+            comment
+                .hashtags
+                .iter()
+                .map(|x| x.text.as_str())
+                .collect::<Vec<_>>(),
+            vec!["rust"]
+        );
+        assert!(!comment.tags_only);
+
+        let comment = parse_comment("thanks @bar");
+        assert_eq!(comment.reply_to, None);
+        assert_eq!(comment.mentions.len(), 1);
+    }
+
+    #[test]
+    fn test_tags_only() {
+        assert!(parse_comment("#follow #like4like").tags_only);
+        assert!(parse_comment("@foo #follow, #like4like 🔥!").tags_only);
+        assert!(!parse_comment("#follow @foo").tags_only);
+        assert!(!parse_comment("@foo").tags_only);
+        assert!(!parse_comment("").tags_only);
+        assert!(!parse_comment("#nice pic").tags_only);
+    }
+}
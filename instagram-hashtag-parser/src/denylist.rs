@@ -0,0 +1,151 @@
+//! Banned or restricted hashtags, matched by [`normalize_hashtag`] key.
+
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+use crate::{normalize_hashtag, Backend, HashtagSpan};
+
+//
+#[derive(Debug, Clone, Default)]
+pub struct Denylist {
+    keys: HashSet<String>,
+    backend: Backend,
+}
+
+impl Denylist {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// One tag per line, with or without the leading `#`. Blank lines are skipped.
+    pub fn from_reader<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut denylist = Self::default();
+        for line in reader.lines() {
+            denylist.insert(line?);
+        }
+        Ok(denylist)
+    }
+
+    pub fn from_path(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Returns false if the entry is blank or already present.
+    pub fn insert(&mut self, tag: impl AsRef<str>) -> bool {
+        let key = key(tag.as_ref());
+        if key.is_empty() {
+            return false;
+        }
+        self.keys.insert(key)
+    }
+
+    pub fn contains(&self, tag: impl AsRef<str>) -> bool {
+        self.keys.contains(&key(tag.as_ref()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The hashtags of `caption` that are on the list, in caption order.
+    pub fn flagged(&self, caption: &str) -> Vec<HashtagSpan> {
+        self.backend
+            .hashtag_spans(caption)
+            .into_iter()
+            .filter(|x| self.keys.contains(&normalize_hashtag(&x.text)))
+            .collect()
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for Denylist {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, tags: I) {
+        for tag in tags {
+            self.insert(tag);
+        }
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for Denylist {
+    fn from_iter<I: IntoIterator<Item = S>>(tags: I) -> Self {
+        let mut denylist = Self::default();
+        denylist.extend(tags);
+        denylist
+    }
+}
+
+fn key(entry: &str) -> String {
+    let key = normalize_hashtag(entry.trim());
+    match key.strip_prefix('#') {
+        Some(key) => key.to_owned(),
+        None => key,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_flagged() {
+        let denylist = ["#Banned", "ＲＥＳＴＲＩＣＴＥＤ", "  ", "banned"]
+            .iter()
+            .collect::<Denylist>();
+        assert_eq!(denylist.len(), 2);
+        assert!(denylist.contains("BANNED"));
+        assert!(denylist.contains("#restricted"));
+        assert!(!denylist.contains("rust"));
+
+        let caption = "#rust #BANNED #Restricted #banned";
+        let flagged = denylist.flagged(caption);
+        assert_eq!(
+            flagged
+                .iter()
+                .map(|x| &caption[x.span.to_owned()])
+                .collect::<Vec<_>>(),
+            vec!["#BANNED", "#Restricted", "#banned"]
+        );
+
+        assert!(Denylist::new().flagged(caption).is_empty());
+    }
+
+    #[test]
+    fn test_from_reader() -> Result<(), Box<dyn error::Error>> {
+        let denylist = Denylist::from_path("tests/files/denylist.txt")?;
+        assert!(denylist.contains("Banned"));
+        assert!(denylist.contains("café"));
+        assert!(!denylist.is_empty());
+
+        let denylist = Denylist::from_reader("foo\n\n#bar\r\n".as_bytes())?;
+        assert_eq!(denylist.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_large() {
+        let denylist = (0..50_000)
+            .map(|i| format!("tag{}", i))
+            .collect::<Denylist>();
+        assert_eq!(denylist.len(), 50_000);
+
+        let caption = (0..100)
+            .map(|i| format!("#TAG{} #other{} ", i * 500, i))
+            .collect::<String>();
+        assert_eq!(denylist.flagged(&caption).len(), 100);
+    }
+}
@@ -0,0 +1,284 @@
+//! Differential testing of the `CrateHashtag` and `Regex` backends.
+//!
+//! Known disagreements are kept in `tests/files/backend_diff.csv`.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{hashtag_spans_with_crate_hashtag, hashtag_spans_with_regex, HashtagSpan};
+
+static CHAR_CLASSES: Lazy<Vec<(CharClass, Regex)>> = Lazy::new(|| {
+    [
+        (CharClass::Whitespace, r"^\s$"),
+        (
+            CharClass::Joiner,
+            r"^[\u{200C}\u{200D}\u{20E3}\p{Variation_Selector}]$",
+        ),
+        (CharClass::Control, r"^\p{C}$"),
+        (
+            CharClass::Emoji,
+            r"^[\p{Extended_Pictographic}\p{Regional_Indicator}\p{Emoji_Modifier}]$",
+        ),
+        (CharClass::Mark, r"^\p{M}$"),
+        (CharClass::Letter, r"^\p{L}$"),
+        (CharClass::Number, r"^\p{N}$"),
+        (CharClass::Punctuation, r"^\p{P}$"),
+        (CharClass::Symbol, r"^\p{S}$"),
+    ]
+    .into_iter()
+    .map(|(class, re)| (class, Regex::new(re).unwrap()))
+    .collect()
+});
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CharClass {
+    Whitespace,
+    /// ZWJ, ZWNJ, variation selectors and the keycap.
+    Joiner,
+    Control,
+    Emoji,
+    Mark,
+    Letter,
+    Number,
+    Punctuation,
+    Symbol,
+    Other,
+}
+
+impl CharClass {
+    pub fn of(c: char) -> Self {
+        let s = c.to_string();
+        CHAR_CLASSES
+            .iter()
+            .find(|(_, re)| re.is_match(&s))
+            .map(|(class, _)| *class)
+            .unwrap_or(Self::Other)
+    }
+}
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DisagreementKind {
+    /// Both found a tag at the same `#` but ended it differently, the class is of the first char
+    /// only one of them kept, e.g. `Punctuation` for `#a，`.
+    TagEnd(CharClass),
+    /// The class is of the first char of the tag.
+    OnlyCrateHashtag(CharClass),
+    /// The class is of the first char of the tag.
+    OnlyRegex(CharClass),
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disagreement {
+    pub kind: DisagreementKind,
+    pub crate_hashtag: Option<HashtagSpan>,
+    pub regex: Option<HashtagSpan>,
+}
+
+/// Runs both backends and pairs their hashtags by the position of the `#`.
+pub fn compare_backends(s: &str) -> Vec<Disagreement> {
+    let mut a = hashtag_spans_with_crate_hashtag(s).into_iter().peekable();
+    let mut b = hashtag_spans_with_regex(s).into_iter().peekable();
+
+    let mut disagreements = vec![];
+    loop {
+        let (x, y) = match (a.peek(), b.peek()) {
+            (None, None) => break,
+            (Some(x), Some(y)) if x.span.start == y.span.start => (a.next(), b.next()),
+            (Some(x), Some(y)) if x.span.start < y.span.start => (a.next(), None),
+            (Some(_), None) => (a.next(), None),
+            _ => (None, b.next()),
+        };
+
+        let kind = match (&x, &y) {
+            (Some(x), Some(y)) => {
+                if x == y {
+                    continue;
+                }
+                let n = x
+                    .text
+                    .chars()
+                    .zip(y.text.chars())
+                    .take_while(|(p, q)| p == q)
+                    .count();
+                let longer = if x.text.chars().count() > n {
+                    &x.text
+                } else {
+                    &y.text
+                };
+                DisagreementKind::TagEnd(
+                    longer
+                        .chars()
+                        .nth(n)
+                        .map(CharClass::of)
+                        .unwrap_or(CharClass::Other),
+                )
+            }
+            (Some(x), None) => DisagreementKind::OnlyCrateHashtag(first_char_class(&x.text)),
+            (None, Some(y)) => DisagreementKind::OnlyRegex(first_char_class(&y.text)),
+            (None, None) => unreachable!(),
+        };
+        disagreements.push(Disagreement {
+            kind,
+            crate_hashtag: x,
+            regex: y,
+        });
+    }
+
+    disagreements
+}
+
+/// Removes chars from `s` as long as the backends still disagree with `kind`.
+pub fn minimize(s: &str, kind: DisagreementKind) -> String {
+    let has_kind = |s: &str| compare_backends(s).iter().any(|x| x.kind == kind);
+
+    let mut chars = s.chars().collect::<Vec<_>>();
+    if !has_kind(s) {
+        return s.to_owned();
+    }
+
+    let mut i = 0;
+    while i < chars.len() {
+        let mut candidate = chars.to_owned();
+        candidate.remove(i);
+        if has_kind(&candidate.iter().collect::<String>()) {
+            chars = candidate;
+        } else {
+            i += 1;
+        }
+    }
+
+    chars.into_iter().collect()
+}
+
+fn first_char_class(text: &str) -> CharClass {
+    text.chars()
+        .next()
+        .map(CharClass::of)
+        .unwrap_or(CharClass::Other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{collections::HashSet, error, fs, io::Write as _};
+
+    use proptest::{prelude::*, strategy::ValueTree as _, test_runner::TestRunner};
+
+    const CORPUS_PATH: &str = "tests/files/backend_diff.csv";
+
+    fn caption() -> impl Strategy<Value = String> {
+        prop::collection::vec(
+            prop_oneof![
+                3 => Just('#'),
+                3 => prop::char::range('a', 'z'),
+                1 => prop::sample::select(vec![' ', '\n', '_', '，', '。', '\u{200D}', '\u{FE0F}', '\u{301}', '😀', '1']),
+                3 => any::<char>(),
+            ],
+            0..16,
+        )
+        .prop_map(|x| x.into_iter().collect())
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Row {
+        caption: String,
+        kind: String,
+    }
+
+    fn corpus() -> Result<Vec<Row>, Box<dyn error::Error>> {
+        let mut rdr = csv::Reader::from_path(CORPUS_PATH)?;
+        Ok(rdr.deserialize::<Row>().collect::<Result<Vec<_>, _>>()?)
+    }
+
+    #[test]
+    fn test_compare_backends() {
+        assert!(compare_backends("#rust is #awesome").is_empty());
+
+        let disagreements = compare_backends("#a，b #c");
+        assert_eq!(disagreements.len(), 1);
+        assert_eq!(
+            disagreements[0].kind,
+            DisagreementKind::TagEnd(CharClass::Punctuation)
+        );
+        assert_eq!(disagreements[0].regex.as_ref().unwrap().text, "a");
+
+        assert_eq!(
+            minimize(
+                "foo #a，b #c",
+                DisagreementKind::TagEnd(CharClass::Punctuation)
+            ),
+            "#a，"
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn backends_never_panic(s in caption()) {
+            for x in compare_backends(&s) {
+                for span in x.crate_hashtag.iter().chain(x.regex.iter()) {
+                    prop_assert!(s.get(span.span.to_owned()).is_some());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn regression_corpus() -> Result<(), Box<dyn error::Error>> {
+        let rows = corpus()?;
+        assert!(!rows.is_empty());
+        for row in rows {
+            assert!(
+                compare_backends(&row.caption)
+                    .iter()
+                    .any(|x| format!("{:?}", x.kind) == row.kind),
+                "caption: {:?}, kind: {}",
+                row.caption,
+                row.kind
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Appends the minimal case of every new kind of disagreement to the corpus.
+    ///
+    /// `cargo test --all-features record_corpus -- --ignored`
+    #[test]
+    #[ignore]
+    fn record_corpus() -> Result<(), Box<dyn error::Error>> {
+        let mut kinds = corpus()?
+            .into_iter()
+            .map(|x| x.kind)
+            .collect::<HashSet<_>>();
+
+        let mut runner = TestRunner::default();
+        let mut rows = vec![];
+        for _ in 0..20_000 {
+            let s = caption()
+                .new_tree(&mut runner)
+                .map_err(|x| x.to_string())?
+                .current();
+            for x in compare_backends(&s) {
+                let kind = format!("{:?}", x.kind);
+                if kinds.insert(kind.to_owned()) {
+                    rows.push((minimize(&s, x.kind), kind));
+                }
+            }
+        }
+
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        for row in rows {
+            wtr.write_record([row.0, row.1])?;
+        }
+        fs::OpenOptions::new()
+            .append(true)
+            .open(CORPUS_PATH)?
+            .write_all(&wtr.into_inner()?)?;
+
+        Ok(())
+    }
+}
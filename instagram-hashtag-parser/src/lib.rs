@@ -1,3 +1,5 @@
+use core::ops::Range;
+
 #[cfg(feature = "with-crate-hashtag")]
 use hashtag::HashtagParser;
 
@@ -7,25 +9,234 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 
 #[cfg(feature = "with-regex")]
-// https://stackoverflow.com/a/31965629/918930
-static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"#((\w|[\u00A1-\uFFFF])+)").unwrap());
+// Letters, marks, digits and connector punctuation, plus emoji with their modifiers,
+// ZWJ/ZWNJ, variation selectors, keycaps and tag sequences.
+// Pictographs that default to text, like `‼` and `™`, only count with VS16 or after a ZWJ.
+// The first char must not be a joiner or a combining char, so `#️⃣` is not a hashtag.
+static RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"#(",
+        r"(?:[\p{L}\p{Nd}\p{Pc}\p{Emoji_Presentation}\p{Regional_Indicator}]|\p{Extended_Pictographic}\u{FE0F})",
+        r"(?:[\p{L}\p{M}\p{Nd}\p{Pc}\p{Emoji_Presentation}\p{Regional_Indicator}\p{Emoji_Modifier}\u{200C}\u{200D}\u{FE0F}\u{20E3}\u{E0020}-\u{E007F}]|\p{Extended_Pictographic}\u{FE0F}|\u{200D}\p{Extended_Pictographic})*",
+        r")"
+    ))
+    .unwrap()
+});
+
+mod normalize;
+pub use normalize::{normalize_hashtag, NormalizedHashtag};
+
+mod mention;
+pub use mention::{mentions, MentionSpan};
+
+mod link;
+pub use link::{hashtag_deep_link, hashtag_url};
+
+#[cfg(all(
+    feature = "with-script",
+    any(feature = "with-crate-hashtag", feature = "with-regex")
+))]
+mod script;
+#[cfg(all(
+    feature = "with-script",
+    any(feature = "with-crate-hashtag", feature = "with-regex")
+))]
+pub use script::{classify_hashtag, hashtag_scripts, hashtag_scripts_with, HashtagScript, Script};
+
+#[cfg(feature = "with-segmentation")]
+mod segment;
+#[cfg(feature = "with-segmentation")]
+pub use segment::{segment_hashtag, Segmentation, Segmenter};
+
+#[cfg(any(feature = "with-crate-hashtag", feature = "with-regex"))]
+mod comment;
+#[cfg(any(feature = "with-crate-hashtag", feature = "with-regex"))]
+pub use comment::{parse_comment, parse_comment_with, ParsedComment};
+
+#[cfg(any(feature = "with-crate-hashtag", feature = "with-regex"))]
+mod trailing;
+#[cfg(any(feature = "with-crate-hashtag", feature = "with-regex"))]
+pub use trailing::{split_trailing_hashtags, split_trailing_hashtags_with, TrailingHashtags};
+
+#[cfg(any(feature = "with-crate-hashtag", feature = "with-regex"))]
+mod validate;
+#[cfg(any(feature = "with-crate-hashtag", feature = "with-regex"))]
+pub use validate::{
+    validate_caption, validate_caption_with, CaptionLimits, Violation, ViolationKind,
+};
+
+#[cfg(any(feature = "with-crate-hashtag", feature = "with-regex"))]
+pub mod analytics;
+#[cfg(any(feature = "with-crate-hashtag", feature = "with-regex"))]
+mod denylist;
+#[cfg(any(feature = "with-crate-hashtag", feature = "with-regex"))]
+pub use denylist::Denylist;
+#[cfg(any(feature = "with-crate-hashtag", feature = "with-regex"))]
+pub mod rewrite;
+#[cfg(any(feature = "with-crate-hashtag", feature = "with-regex"))]
+pub mod stream;
+
+#[cfg(all(
+    feature = "with-rayon",
+    any(feature = "with-crate-hashtag", feature = "with-regex")
+))]
+mod batch;
+#[cfg(all(
+    feature = "with-rayon",
+    any(feature = "with-crate-hashtag", feature = "with-regex")
+))]
+pub use batch::{
+    extract_batch, extract_batch_with_backend, extract_spans_batch,
+    extract_spans_batch_with_backend,
+};
+
+#[cfg(all(test, feature = "with-crate-hashtag", feature = "with-regex"))]
+mod diff;
+
+#[cfg(feature = "with-wasm")]
+pub mod wasm;
 
 #[cfg(feature = "with-crate-hashtag")]
 pub fn hashtags_with_crate_hashtag(s: &str) -> Vec<String> {
     HashtagParser::new(s)
-        .into_iter()
         .map(|tag| tag.text.to_string())
         .collect::<Vec<_>>()
 }
 
 #[cfg(feature = "with-regex")]
 pub fn hashtags_with_regex(s: &str) -> Vec<String> {
+    RE.captures_iter(s).map(|x| x[1].to_string()).collect()
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HashtagSpan {
+    /// The tag without the leading `#`.
+    pub text: String,
+    /// Byte range in the caption, from the `#` to the end of the tag.
+    pub span: Range<usize>,
+}
+
+#[cfg(feature = "with-crate-hashtag")]
+pub fn hashtag_spans_with_crate_hashtag(s: &str) -> Vec<HashtagSpan> {
+    hashtag_spans_with_crate_hashtag_in(s, &mut Vec::new())
+}
+
+/// `offsets` is scratch space for the char offset table, so callers can reuse it.
+#[cfg(feature = "with-crate-hashtag")]
+pub(crate) fn hashtag_spans_with_crate_hashtag_in(
+    s: &str,
+    offsets: &mut Vec<usize>,
+) -> Vec<HashtagSpan> {
+    // the crate reports inclusive char indexes
+    offsets.clear();
+    offsets.extend(
+        s.char_indices()
+            .map(|(i, _)| i)
+            .chain(core::iter::once(s.len())),
+    );
+
+    HashtagParser::new(s)
+        .filter_map(|tag| {
+            let start = *offsets.get(tag.start)?;
+            let end = *offsets.get(tag.end + 1)?;
+            Some(HashtagSpan {
+                text: tag.text.into_owned(),
+                span: start..end,
+            })
+        })
+        .collect()
+}
+
+#[cfg(feature = "with-regex")]
+pub fn hashtag_spans_with_regex(s: &str) -> Vec<HashtagSpan> {
     RE.captures_iter(s)
+        .map(|x| HashtagSpan {
+            text: x[1].to_string(),
+            span: x.get(0).map(|m| m.range()).unwrap_or_default(),
+        })
+        .collect()
+}
+
+#[cfg(feature = "with-crate-hashtag")]
+pub fn normalized_hashtags_with_crate_hashtag(s: &str) -> Vec<NormalizedHashtag> {
+    hashtags_with_crate_hashtag(s)
         .into_iter()
-        .map(|x| x[1].to_string())
+        .map(NormalizedHashtag::new)
         .collect()
 }
 
+#[cfg(feature = "with-regex")]
+pub fn normalized_hashtags_with_regex(s: &str) -> Vec<NormalizedHashtag> {
+    hashtags_with_regex(s)
+        .into_iter()
+        .map(NormalizedHashtag::new)
+        .collect()
+}
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backend {
+    #[cfg(feature = "with-crate-hashtag")]
+    CrateHashtag,
+    #[cfg(feature = "with-regex")]
+    Regex,
+}
+
+/// `CrateHashtag` when `with-crate-hashtag` is enabled, otherwise `Regex`.
+#[cfg(any(feature = "with-crate-hashtag", feature = "with-regex"))]
+impl Default for Backend {
+    fn default() -> Self {
+        #[cfg(feature = "with-crate-hashtag")]
+        return Self::CrateHashtag;
+        #[cfg(not(feature = "with-crate-hashtag"))]
+        return Self::Regex;
+    }
+}
+
+#[cfg(any(feature = "with-crate-hashtag", feature = "with-regex"))]
+impl Backend {
+    pub fn hashtags(&self, s: &str) -> Vec<String> {
+        match *self {
+            #[cfg(feature = "with-crate-hashtag")]
+            Self::CrateHashtag => hashtags_with_crate_hashtag(s),
+            #[cfg(feature = "with-regex")]
+            Self::Regex => hashtags_with_regex(s),
+        }
+    }
+
+    pub fn hashtag_spans(&self, s: &str) -> Vec<HashtagSpan> {
+        match *self {
+            #[cfg(feature = "with-crate-hashtag")]
+            Self::CrateHashtag => hashtag_spans_with_crate_hashtag(s),
+            #[cfg(feature = "with-regex")]
+            Self::Regex => hashtag_spans_with_regex(s),
+        }
+    }
+
+    // only the crate-hashtag backend needs the offset table
+    #[cfg_attr(not(feature = "with-rayon"), allow(dead_code))]
+    #[cfg_attr(
+        not(feature = "with-crate-hashtag"),
+        allow(unused_variables, clippy::ptr_arg)
+    )]
+    pub(crate) fn hashtag_spans_in(&self, s: &str, offsets: &mut Vec<usize>) -> Vec<HashtagSpan> {
+        match *self {
+            #[cfg(feature = "with-crate-hashtag")]
+            Self::CrateHashtag => hashtag_spans_with_crate_hashtag_in(s, offsets),
+            #[cfg(feature = "with-regex")]
+            Self::Regex => hashtag_spans_with_regex(s),
+        }
+    }
+
+    pub fn normalized_hashtags(&self, s: &str) -> Vec<NormalizedHashtag> {
+        self.hashtags(s)
+            .into_iter()
+            .map(NormalizedHashtag::new)
+            .collect()
+    }
+}
+
 #[cfg(feature = "with-crate-hashtag")]
 #[cfg(test)]
 mod tests_with_crate_hashtag {
@@ -43,6 +254,41 @@ mod tests_with_crate_hashtag {
             vec!["我".to_owned(), "我的".to_owned()]
         );
     }
+
+    #[test]
+    fn spans() {
+        let s = "#rust is #awesome，#我的 #café";
+        let spans = hashtag_spans_with_crate_hashtag(s);
+        assert_eq!(
+            spans.iter().map(|x| x.text.as_str()).collect::<Vec<_>>(),
+            hashtags_with_crate_hashtag(s)
+        );
+        for x in spans {
+            assert_eq!(&s[x.span], format!("#{}", x.text));
+        }
+
+        assert_eq!(
+            hashtag_spans_with_crate_hashtag("a #我的 b"),
+            vec![HashtagSpan {
+                text: "我的".to_owned(),
+                span: 2..9
+            }]
+        );
+    }
+
+    #[test]
+    fn normalized() {
+        assert_eq!(
+            normalized_hashtags_with_crate_hashtag("#Rust #ＲＵＳＴ")
+                .into_iter()
+                .map(|x| (x.text, x.key))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Rust".to_owned(), "rust".to_owned()),
+                ("ＲＵＳＴ".to_owned(), "rust".to_owned())
+            ]
+        );
+    }
 }
 
 #[cfg(feature = "with-regex")]
@@ -61,5 +307,81 @@ mod tests_with_regex {
             hashtags_with_regex("#我#我的"),
             vec!["我".to_owned(), "我的".to_owned()]
         );
+
+        assert_eq!(
+            hashtags_with_regex("#东京。#大阪、#京都… #旅行！"),
+            vec![
+                "东京".to_owned(),
+                "大阪".to_owned(),
+                "京都".to_owned(),
+                "旅行".to_owned()
+            ]
+        );
+
+        assert_eq!(hashtags_with_regex("#𠜎𠜱"), vec!["𠜎𠜱".to_owned()]);
+
+        assert_eq!(hashtags_with_regex("#️⃣ #\u{0301}"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn regression_corpus() {
+        #[derive(serde::Deserialize)]
+        struct Row {
+            caption: String,
+            hashtags: String,
+        }
+
+        let data = include_str!("../tests/files/regex_regression.csv");
+        let mut rdr = csv::Reader::from_reader(data.as_bytes());
+        let mut n = 0;
+        for row in rdr.deserialize::<Row>() {
+            let row = row.unwrap();
+            assert_eq!(
+                hashtags_with_regex(&row.caption),
+                row.hashtags
+                    .split_whitespace()
+                    .map(ToOwned::to_owned)
+                    .collect::<Vec<_>>(),
+                "caption: {:?}",
+                row.caption
+            );
+            n += 1;
+        }
+        assert!(n > 0);
+    }
+
+    #[test]
+    fn spans() {
+        let s = "#rust is #awesome，#我的 #café";
+        let spans = hashtag_spans_with_regex(s);
+        assert_eq!(
+            spans.iter().map(|x| x.text.as_str()).collect::<Vec<_>>(),
+            hashtags_with_regex(s)
+        );
+        for x in spans {
+            assert_eq!(&s[x.span], format!("#{}", x.text));
+        }
+
+        assert_eq!(
+            hashtag_spans_with_regex("a #我的 b"),
+            vec![HashtagSpan {
+                text: "我的".to_owned(),
+                span: 2..9
+            }]
+        );
+    }
+
+    #[test]
+    fn normalized() {
+        assert_eq!(
+            normalized_hashtags_with_regex("#Rust #ＲＵＳＴ")
+                .into_iter()
+                .map(|x| (x.text, x.key))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Rust".to_owned(), "rust".to_owned()),
+                ("ＲＵＳＴ".to_owned(), "rust".to_owned())
+            ]
+        );
     }
 }
@@ -0,0 +1,60 @@
+//! Links to the page of a hashtag.
+
+/// `https://www.instagram.com/explore/tags/<tag>/`, non ASCII tags are percent-encoded.
+///
+/// A leading `#` is removed, the tag is otherwise kept as is,
+/// pass it through [`normalize_hashtag`](crate::normalize_hashtag) first to get one link per tag.
+pub fn hashtag_url(tag: &str) -> String {
+    format!(
+        "https://www.instagram.com/explore/tags/{}/",
+        percent_encode(tag.strip_prefix('#').unwrap_or(tag))
+    )
+}
+
+/// `instagram://tag?name=<tag>`, opens the hashtag page in the app.
+pub fn hashtag_deep_link(tag: &str) -> String {
+    format!(
+        "instagram://tag?name={}",
+        percent_encode(tag.strip_prefix('#').unwrap_or(tag))
+    )
+}
+
+/// Encodes everything but the RFC 3986 unreserved chars.
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashtag_url() {
+        assert_eq!(
+            hashtag_url("#rust"),
+            "https://www.instagram.com/explore/tags/rust/"
+        );
+        assert_eq!(
+            hashtag_url("東京タワー"),
+            "https://www.instagram.com/explore/tags/%E6%9D%B1%E4%BA%AC%E3%82%BF%E3%83%AF%E3%83%BC/"
+        );
+        assert_eq!(
+            hashtag_url("café_2021"),
+            "https://www.instagram.com/explore/tags/caf%C3%A9_2021/"
+        );
+    }
+
+    #[test]
+    fn test_hashtag_deep_link() {
+        assert_eq!(hashtag_deep_link("#rust"), "instagram://tag?name=rust");
+        assert_eq!(hashtag_deep_link("a&b c"), "instagram://tag?name=a%26b%20c");
+    }
+}
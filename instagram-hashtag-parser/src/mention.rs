@@ -0,0 +1,86 @@
+use core::ops::Range;
+
+pub const USERNAME_MAX_LEN: usize = 30;
+
+//
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MentionSpan {
+    /// The username without the leading `@`.
+    pub username: String,
+    /// Byte range in the caption, from the `@` to the end of the username.
+    pub span: Range<usize>,
+}
+
+/// Finds `@username` mentions.
+///
+/// A username is 1 to 30 ASCII letters, digits, `_` or `.`, and doesn't end with a `.`.
+/// The `@` must not follow a username char, so emails like `foo@example.com` are skipped.
+pub fn mentions(s: &str) -> Vec<MentionSpan> {
+    let bytes = s.as_bytes();
+    let mut mentions = vec![];
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'@' || (i > 0 && (is_username_byte(bytes[i - 1]) || bytes[i - 1] == b'@')) {
+            i += 1;
+            continue;
+        }
+
+        let start = i + 1;
+        let mut end = start;
+        while end < bytes.len() && is_username_byte(bytes[end]) {
+            end += 1;
+        }
+        let next = end;
+        while end > start && bytes[end - 1] == b'.' {
+            end -= 1;
+        }
+
+        if end > start && end - start <= USERNAME_MAX_LEN {
+            mentions.push(MentionSpan {
+                username: s[start..end].to_owned(),
+                span: i..end,
+            });
+        }
+        i = next.max(i + 1);
+    }
+
+    mentions
+}
+
+pub(crate) fn is_username_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'.'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mentions() {
+        assert_eq!(
+            mentions("hi @foo and @bar.baz_1. and foo@example.com @"),
+            vec![
+                MentionSpan {
+                    username: "foo".to_owned(),
+                    span: 3..7
+                },
+                MentionSpan {
+                    username: "bar.baz_1".to_owned(),
+                    span: 12..22
+                },
+            ]
+        );
+
+        assert_eq!(
+            mentions("@我 (@foo) @@bar")
+                .into_iter()
+                .map(|x| x.username)
+                .collect::<Vec<_>>(),
+            vec!["foo".to_owned()]
+        );
+
+        assert!(mentions(&format!("@{}", "a".repeat(31))).is_empty());
+        assert_eq!(mentions(&format!("@{}", "a".repeat(30))).len(), 1);
+    }
+}
@@ -0,0 +1,108 @@
+use caseless::Caseless as _;
+use unicode_normalization::UnicodeNormalization as _;
+
+//
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NormalizedHashtag {
+    /// The tag as it was written in the caption, without the leading `#`.
+    pub text: String,
+    /// The key used to group variants of the same tag, see [`normalize_hashtag`].
+    pub key: String,
+}
+
+impl NormalizedHashtag {
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let key = normalize_hashtag(&text);
+        Self { text, key }
+    }
+}
+
+/// Applies NFKC, Unicode default case folding and removes invisible characters,
+/// so `Rust`, `rust`, `ＲＵＳＴ` and `r\u{200B}ust` all map to `rust`.
+///
+/// `tag` is the tag text without the leading `#`.
+pub fn normalize_hashtag(tag: impl AsRef<str>) -> String {
+    tag.as_ref()
+        .chars()
+        .filter(|c| !is_invisible(*c))
+        .nfkc()
+        .default_case_fold()
+        .nfkc()
+        .collect()
+}
+
+/// Ref https://www.unicode.org/Public/UCD/latest/ucd/DerivedCoreProperties.txt Default_Ignorable_Code_Point
+///
+/// ZWNJ, ZWJ, VS16 and the tag characters are kept, they change the word in Persian and the emoji
+/// in ZWJ sequences and subdivision flags.
+pub(crate) fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{034F}'
+            | '\u{061C}'
+            | '\u{115F}'..='\u{1160}'
+            | '\u{17B4}'..='\u{17B5}'
+            | '\u{180B}'..='\u{180F}'
+            | '\u{200B}'
+            | '\u{200E}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{206F}'
+            | '\u{3164}'
+            | '\u{FE00}'..='\u{FE0E}'
+            | '\u{FEFF}'
+            | '\u{FFA0}'
+            | '\u{FFF0}'..='\u{FFF8}'
+            | '\u{1BCA0}'..='\u{1BCA3}'
+            | '\u{1D173}'..='\u{1D17A}'
+            | '\u{E0000}'..='\u{E001F}'
+            | '\u{E0080}'..='\u{E0FFF}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_hashtag() {
+        assert_eq!(normalize_hashtag("Rust"), "rust");
+        assert_eq!(normalize_hashtag("rust"), "rust");
+        assert_eq!(normalize_hashtag("ＲＵＳＴ"), "rust");
+        assert_eq!(normalize_hashtag("ru\u{200B}st\u{FEFF}"), "rust");
+
+        // NFD and NFC
+        assert_eq!(normalize_hashtag("Cafe\u{0301}"), "café");
+        assert_eq!(normalize_hashtag("CAFÉ"), "café");
+
+        // full case folding
+        assert_eq!(normalize_hashtag("Straße"), "strasse");
+        assert_eq!(normalize_hashtag("ΣΟΦΟΣ"), normalize_hashtag("σοφος"));
+
+        assert_eq!(normalize_hashtag("我的"), "我的");
+
+        // kept in the key
+        assert_eq!(normalize_hashtag("می\u{200C}خواهم"), "می\u{200C}خواهم");
+        assert_eq!(
+            normalize_hashtag("👨\u{200D}👩\u{200D}👧"),
+            "👨\u{200D}👩\u{200D}👧"
+        );
+        assert_eq!(normalize_hashtag("❤\u{FE0F}"), "❤\u{FE0F}");
+        let scotland = "🏴\u{E0067}\u{E0062}\u{E0073}\u{E0063}\u{E0074}\u{E007F}";
+        assert_eq!(normalize_hashtag(scotland), scotland);
+
+        assert_eq!(normalize_hashtag(""), "");
+    }
+
+    #[test]
+    fn test_normalized_hashtag() {
+        assert_eq!(
+            NormalizedHashtag::new("ＲＵＳＴ"),
+            NormalizedHashtag {
+                text: "ＲＵＳＴ".to_owned(),
+                key: "rust".to_owned()
+            }
+        );
+    }
+}
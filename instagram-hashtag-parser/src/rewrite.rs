@@ -0,0 +1,224 @@
+//! Removing and replacing hashtags in a caption while keeping its whitespace and line breaks tidy.
+
+use crate::{normalize_hashtag, split_trailing_hashtags, Backend, HashtagSpan};
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HashtagEdit {
+    Keep,
+    Remove,
+    /// The new tag, with or without the leading `#`.
+    Replace(String),
+}
+
+pub fn rewrite_hashtags<F>(caption: &str, f: F) -> String
+where
+    F: FnMut(&HashtagSpan) -> HashtagEdit,
+{
+    rewrite_hashtags_with(caption, Backend::default(), f)
+}
+
+/// Applies `f` to every hashtag.
+///
+/// When a hashtag is removed, the whitespace around it is collapsed, a line left with nothing
+/// but removed hashtags is dropped, and whitespace left at the end of the caption is trimmed.
+pub fn rewrite_hashtags_with<F>(caption: &str, backend: Backend, mut f: F) -> String
+where
+    F: FnMut(&HashtagSpan) -> HashtagEdit,
+{
+    let mut out = String::with_capacity(caption.len());
+    let mut last = 0;
+
+    for hashtag in backend.hashtag_spans(caption) {
+        if hashtag.span.start < last {
+            continue;
+        }
+
+        let edit = f(&hashtag);
+        if edit == HashtagEdit::Keep {
+            continue;
+        }
+
+        out.push_str(&caption[last..hashtag.span.start]);
+        last = hashtag.span.end;
+
+        match edit {
+            HashtagEdit::Keep => unreachable!(),
+            HashtagEdit::Replace(tag) => {
+                out.push('#');
+                out.push_str(tag.strip_prefix('#').unwrap_or(&tag));
+            }
+            HashtagEdit::Remove => {
+                let right_ws_end = last
+                    + caption[last..]
+                        .find(|c| !is_horizontal_whitespace(c))
+                        .unwrap_or(caption.len() - last);
+                let right = caption[right_ws_end..].chars().next();
+                let at_line_start = out.is_empty() || out.ends_with('\n');
+
+                match right {
+                    None => {
+                        let len = out.trim_end().len();
+                        out.truncate(len);
+                        last = caption.len();
+                    }
+                    Some('\r') | Some('\n') => {
+                        trim_end_horizontal_whitespace(&mut out);
+                        last = right_ws_end;
+                        if at_line_start {
+                            // the whole line was hashtags
+                            if caption[last..].starts_with("\r\n") {
+                                last += 2;
+                            } else {
+                                last += 1;
+                            }
+                        }
+                    }
+                    Some(c) => {
+                        if right_ws_end > last {
+                            if at_line_start || out.ends_with(is_horizontal_whitespace) {
+                                last = right_ws_end;
+                            }
+                        } else if !c.is_alphanumeric() {
+                            trim_end_horizontal_whitespace(&mut out);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    out.push_str(&caption[last..]);
+    out
+}
+
+pub fn strip_hashtags(caption: &str) -> String {
+    rewrite_hashtags(caption, |_| HashtagEdit::Remove)
+}
+
+/// Replaces hashtags by [`normalize_hashtag`] key, e.g. `[("ad", "sponsored")]` turns `#Ad` into `#sponsored`.
+pub fn replace_hashtags(caption: &str, replacements: &[(&str, &str)]) -> String {
+    let replacements = replacements
+        .iter()
+        .map(|(from, to)| (normalize_hashtag(from.trim_start_matches('#')), *to))
+        .collect::<Vec<_>>();
+
+    rewrite_hashtags(caption, |hashtag| {
+        let key = normalize_hashtag(&hashtag.text);
+        match replacements.iter().find(|(from, _)| *from == key) {
+            Some((_, to)) => HashtagEdit::Replace((*to).to_owned()),
+            None => HashtagEdit::Keep,
+        }
+    })
+}
+
+/// Moves the trailing hashtag block, see [`split_trailing_hashtags`], into a first comment.
+///
+/// Returns the caption without the block and its separators, and the comment,
+/// which is empty when there is nothing to move.
+pub fn move_trailing_hashtags(caption: &str) -> (String, String) {
+    match split_trailing_hashtags(caption) {
+        Some(trailing) => (
+            trailing.body.to_owned(),
+            trailing
+                .hashtags
+                .iter()
+                .map(|x| &caption[x.span.to_owned()])
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        None => (caption.to_owned(), "".to_owned()),
+    }
+}
+
+fn is_horizontal_whitespace(c: char) -> bool {
+    c.is_whitespace() && c != '\n' && c != '\r'
+}
+
+fn trim_end_horizontal_whitespace(s: &mut String) {
+    let len = s.trim_end_matches(is_horizontal_whitespace).len();
+    s.truncate(len);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_hashtags() {
+        assert_eq!(strip_hashtags("#rust is #awesome"), "is");
+        assert_eq!(strip_hashtags("I love #rust and #go"), "I love and");
+        assert_eq!(strip_hashtags("I love #rust, really"), "I love, really");
+        assert_eq!(strip_hashtags("a #b #c d"), "a d");
+        assert_eq!(strip_hashtags("#b #c d"), "d");
+        assert_eq!(strip_hashtags("foo #bar\nbaz"), "foo\nbaz");
+        assert_eq!(strip_hashtags("foo\n#a #b\nbar"), "foo\nbar");
+        assert_eq!(strip_hashtags("foo\r\n#a #b\r\nbar"), "foo\r\nbar");
+        assert_eq!(strip_hashtags("foo\n\nbar #a"), "foo\n\nbar");
+        assert_eq!(strip_hashtags("Sunset\n\n#a #b\n#c"), "Sunset");
+        assert_eq!(strip_hashtags("\tindented #a text"), "\tindented text");
+        assert_eq!(strip_hashtags("no tags  here "), "no tags  here ");
+        assert_eq!(strip_hashtags("#a"), "");
+        assert_eq!(strip_hashtags("#a\nbar"), "bar");
+    }
+
+    #[test]
+    fn test_replace_hashtags() {
+        assert_eq!(
+            replace_hashtags(
+                "New drop #Ad #rust, #AD!",
+                &[("#ad", "sponsored"), ("go", "#golang")]
+            ),
+            "New drop #sponsored #rust, #sponsored!"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_hashtags() {
+        assert_eq!(
+            rewrite_hashtags("#a #b #c", |x| match x.text.as_str() {
+                "a" => HashtagEdit::Replace("x".to_owned()),
+                "b" => HashtagEdit::Remove,
+                _ => HashtagEdit::Keep,
+            }),
+            "#x #c"
+        );
+    }
+
+    #[test]
+    fn test_move_trailing_hashtags() {
+        assert_eq!(
+            move_trailing_hashtags("Sunset at the #beach\n\n#travel #sun\n#sea\n"),
+            (
+                "Sunset at the #beach".to_owned(),
+                "#travel #sun #sea".to_owned()
+            )
+        );
+        assert_eq!(
+            move_trailing_hashtags("Sunset at the #beach\n.\n.\n#travel #sun\n#sea\n"),
+            (
+                "Sunset at the #beach".to_owned(),
+                "#travel #sun #sea".to_owned()
+            )
+        );
+        assert_eq!(
+            move_trailing_hashtags("no #tags here"),
+            ("no #tags here".to_owned(), "".to_owned())
+        );
+        assert_eq!(
+            move_trailing_hashtags("Sunset at the #beach #sun"),
+            ("Sunset at the #beach #sun".to_owned(), "".to_owned())
+        );
+        assert_eq!(
+            move_trailing_hashtags("Sunset\n#travel\nat the #beach #sun"),
+            (
+                "Sunset\n#travel\nat the #beach #sun".to_owned(),
+                "".to_owned()
+            )
+        );
+        assert_eq!(
+            move_trailing_hashtags("#only #tags"),
+            ("".to_owned(), "#only #tags".to_owned())
+        );
+    }
+}
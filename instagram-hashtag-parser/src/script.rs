@@ -0,0 +1,186 @@
+//! The Unicode script of a hashtag, for bucketing tags by region.
+
+pub use unicode_script::Script;
+use unicode_script::UnicodeScript as _;
+
+use crate::{Backend, HashtagSpan};
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HashtagScript {
+    /// The script with the most chars, `Common` when there are only digits, emoji and the like.
+    pub script: Script,
+    /// More than one script, Han with Hiragana, Katakana or Hangul doesn't count.
+    pub mixed: bool,
+    /// ISO 639-1 code guessed from the scripts, `None` for scripts shared by many languages like Latin.
+    pub language: Option<&'static str>,
+}
+
+pub fn classify_hashtag(tag: &str) -> HashtagScript {
+    let mut counts: Vec<(Script, usize)> = vec![];
+    for script in tag
+        .chars()
+        .map(|c| c.script())
+        .filter(|x| !matches!(x, Script::Common | Script::Inherited | Script::Unknown))
+    {
+        match counts.iter_mut().find(|(x, _)| *x == script) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((script, 1)),
+        }
+    }
+
+    // the first one wins a tie
+    let script = counts
+        .iter()
+        .rev()
+        .max_by_key(|(_, n)| *n)
+        .map(|(x, _)| *x)
+        .unwrap_or(Script::Common);
+
+    let has = |script: Script| counts.iter().any(|(x, _)| *x == script);
+    let is_cjk = |script: Script| {
+        matches!(
+            script,
+            Script::Han | Script::Hiragana | Script::Katakana | Script::Hangul
+        )
+    };
+    let mixed = if counts.iter().all(|(x, _)| is_cjk(*x)) {
+        // Japanese doesn't use Hangul, Korean doesn't use kana
+        has(Script::Hangul) && (has(Script::Hiragana) || has(Script::Katakana))
+    } else {
+        counts.len() > 1
+    };
+
+    let language = if mixed {
+        None
+    } else if has(Script::Hiragana) || has(Script::Katakana) {
+        Some("ja")
+    } else if has(Script::Hangul) {
+        Some("ko")
+    } else {
+        match script {
+            Script::Han => Some("zh"),
+            Script::Thai => Some("th"),
+            Script::Greek => Some("el"),
+            Script::Hebrew => Some("he"),
+            Script::Armenian => Some("hy"),
+            Script::Georgian => Some("ka"),
+            Script::Bengali => Some("bn"),
+            Script::Tamil => Some("ta"),
+            Script::Telugu => Some("te"),
+            Script::Gujarati => Some("gu"),
+            Script::Kannada => Some("kn"),
+            Script::Malayalam => Some("ml"),
+            Script::Gurmukhi => Some("pa"),
+            Script::Khmer => Some("km"),
+            Script::Lao => Some("lo"),
+            Script::Myanmar => Some("my"),
+            Script::Sinhala => Some("si"),
+            Script::Ethiopic => Some("am"),
+            Script::Arabic => Some(guess_arabic(tag)),
+            Script::Cyrillic => Some(guess_cyrillic(tag)),
+            Script::Devanagari => Some("hi"),
+            _ => None,
+        }
+    };
+
+    HashtagScript {
+        script,
+        mixed,
+        language,
+    }
+}
+
+pub fn hashtag_scripts(caption: &str) -> Vec<(HashtagSpan, HashtagScript)> {
+    hashtag_scripts_with(caption, Backend::default())
+}
+
+pub fn hashtag_scripts_with(caption: &str, backend: Backend) -> Vec<(HashtagSpan, HashtagScript)> {
+    backend
+        .hashtag_spans(caption)
+        .into_iter()
+        .map(|x| {
+            let script = classify_hashtag(&x.text);
+            (x, script)
+        })
+        .collect()
+}
+
+/// Persian letters that Arabic doesn't use, otherwise `ar`.
+fn guess_arabic(tag: &str) -> &'static str {
+    if tag
+        .chars()
+        .any(|c| matches!(c, 'پ' | 'چ' | 'ژ' | 'گ' | 'ک' | 'ی'))
+    {
+        "fa"
+    } else {
+        "ar"
+    }
+}
+
+/// Ukrainian and Serbian letters that Russian doesn't use, otherwise `ru`.
+fn guess_cyrillic(tag: &str) -> &'static str {
+    if tag
+        .chars()
+        .any(|c| matches!(c, 'і' | 'ї' | 'є' | 'ґ' | 'І' | 'Ї' | 'Є' | 'Ґ'))
+    {
+        "uk"
+    } else if tag
+        .chars()
+        .any(|c| matches!(c, 'ђ' | 'ћ' | 'џ' | 'љ' | 'њ' | 'Ђ' | 'Ћ' | 'Џ' | 'Љ' | 'Њ'))
+    {
+        "sr"
+    } else {
+        "ru"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_hashtag() {
+        for (tag, script, mixed, language) in [
+            ("rust", Script::Latin, false, None),
+            ("東京タワー", Script::Han, false, Some("ja")),
+            ("上海迪士尼", Script::Han, false, Some("zh")),
+            ("서울여행", Script::Hangul, false, Some("ko")),
+            ("السعودية", Script::Arabic, false, Some("ar")),
+            ("ایران_گردی", Script::Arabic, false, Some("fa")),
+            ("москва", Script::Cyrillic, false, Some("ru")),
+            ("київ", Script::Cyrillic, false, Some("uk")),
+            ("กรุงเทพ", Script::Thai, false, Some("th")),
+            ("2021😀", Script::Common, false, None),
+            // Cyrillic `а` and `о` in a Latin tag
+            ("fаshiоn", Script::Latin, true, None),
+            ("東京tokyo", Script::Latin, true, None),
+            ("서울タワー", Script::Hangul, true, None),
+        ] {
+            assert_eq!(
+                classify_hashtag(tag),
+                HashtagScript {
+                    script,
+                    mixed,
+                    language
+                },
+                "tag: {}",
+                tag
+            );
+        }
+    }
+
+    #[test]
+    fn test_hashtag_scripts() {
+        assert_eq!(
+            hashtag_scripts("#rust #東京")
+                .into_iter()
+                .map(|(x, y)| (x.text, y.script))
+                .collect::<Vec<_>>(),
+            vec![
+                ("rust".to_owned(), Script::Latin),
+                ("東京".to_owned(), Script::Han)
+            ]
+        );
+    }
+}
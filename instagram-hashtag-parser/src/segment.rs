@@ -0,0 +1,306 @@
+//! Splitting concatenated hashtags like `#goodmorningworld` or `#GoodMorningWorld` into words.
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+#[cfg(feature = "with-serde")]
+use serde::{Deserialize, Serialize};
+
+/// The 80000 most frequent words of English Wikipedia, most frequent first.
+///
+/// From [wordninja](https://github.com/keredson/wordninja), see `segment_words.LICENSE-MIT`.
+const WORDS: &str = include_str!("segment_words.txt");
+
+/// Instagram and tech words missing from [`WORDS`].
+const EXTRA_WORDS: &str = include_str!("segment_words_extra.txt");
+// The rank given to the extra words.
+const EXTRA_WORDS_RANK: usize = 5000;
+
+static SEGMENTER: Lazy<Segmenter> = Lazy::new(|| {
+    Segmenter::from_words(
+        WORDS
+            .lines()
+            .take(EXTRA_WORDS_RANK)
+            .chain(EXTRA_WORDS.lines())
+            .chain(WORDS.lines().skip(EXTRA_WORDS_RANK)),
+    )
+});
+
+// An unknown run costs more than any dictionary word of the same length,
+// and a per-run base so that unknown chars stick together.
+const UNKNOWN_BASE_COST: f64 = 20.0;
+const UNKNOWN_CHAR_COST: f64 = 3.0;
+// Very short words are frequent but rarely meant, `awesome` is not `a`, `we` and `some`.
+const SHORT_WORD_LEN: usize = 2;
+const SHORT_WORD_COST: f64 = 6.0;
+
+//
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+pub struct Segmentation {
+    /// The words as written in the tag, without `_` separators.
+    pub words: Vec<String>,
+    /// Share of the chars covered by dictionary words or digits, from `0.0` to `1.0`.
+    pub confidence: f64,
+}
+
+/// Segments with the bundled English dictionary, see [`Segmenter::segment`].
+pub fn segment_hashtag(tag: &str) -> Segmentation {
+    SEGMENTER.segment(tag)
+}
+
+//
+#[derive(Debug, Clone)]
+pub struct Segmenter {
+    costs: HashMap<String, f64>,
+    max_len: usize,
+}
+
+impl Default for Segmenter {
+    fn default() -> Self {
+        SEGMENTER.to_owned()
+    }
+}
+
+impl Segmenter {
+    /// `words` are ranked, most frequent first, the cost of a word follows Zipf's law.
+    /// Words are matched case-insensitively on ASCII letters.
+    pub fn from_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let words = words
+            .into_iter()
+            .map(|x| x.as_ref().trim().to_ascii_lowercase())
+            .filter(|x| !x.is_empty())
+            .collect::<Vec<_>>();
+        let ln_n = (words.len().max(2) as f64).ln();
+
+        let mut costs = HashMap::with_capacity(words.len());
+        let mut max_len = 0;
+        for (rank, word) in words.into_iter().enumerate() {
+            let len = word.len();
+            max_len = max_len.max(len);
+            costs.entry(word).or_insert_with(|| {
+                let cost = ((rank + 1) as f64 * ln_n).ln();
+                if len <= SHORT_WORD_LEN {
+                    cost + SHORT_WORD_COST
+                } else {
+                    cost
+                }
+            });
+        }
+
+        Self { costs, max_len }
+    }
+
+    /// Splits on `_`, camel case (`NASAPhotos` is `NASA` and `Photos`) and digits.
+    /// Unless the tag is camel cased, letter runs are then split by the most likely word sequence.
+    ///
+    /// A leading `#` is ignored. Runs with non ASCII letters are kept whole and count as unknown.
+    pub fn segment(&self, tag: &str) -> Segmentation {
+        let tag = tag.strip_prefix('#').unwrap_or(tag);
+
+        let camel_case = tag.chars().any(char::is_uppercase) && tag.chars().any(char::is_lowercase);
+
+        let mut words = vec![];
+        let mut known = 0;
+        let mut total = 0;
+        for (chunk, class) in chunks(tag) {
+            let len = chunk.chars().count();
+            total += len;
+            match class {
+                ChunkClass::Digits => {
+                    known += len;
+                    words.push(chunk.to_owned());
+                }
+                ChunkClass::Other => words.push(chunk.to_owned()),
+                ChunkClass::Letters if !chunk.is_ascii() => words.push(chunk.to_owned()),
+                ChunkClass::Letters if camel_case => {
+                    if self.costs.contains_key(&chunk.to_ascii_lowercase()) {
+                        known += len;
+                    }
+                    words.push(chunk.to_owned());
+                }
+                ChunkClass::Letters => {
+                    for (word, is_known) in self.split_letters(chunk) {
+                        if is_known {
+                            known += word.len();
+                        }
+                        words.push(word.to_owned());
+                    }
+                }
+            }
+        }
+
+        Segmentation {
+            words,
+            confidence: if total == 0 {
+                0.0
+            } else {
+                known as f64 / total as f64
+            },
+        }
+    }
+
+    fn split_letters<'a>(&self, chunk: &'a str) -> Vec<(&'a str, bool)> {
+        let lower = chunk.to_ascii_lowercase();
+        let n = lower.len();
+
+        // best[i] is the cost of chunk[..i], with the start of its last segment
+        let mut best: Vec<(f64, usize, bool)> = vec![(f64::INFINITY, 0, false); n + 1];
+        best[0].0 = 0.0;
+        for i in 1..=n {
+            for j in i.saturating_sub(self.max_len.max(1))..i {
+                let (cost, is_known) = match self.costs.get(&lower[j..i]) {
+                    Some(cost) => (*cost, true),
+                    None => (
+                        UNKNOWN_BASE_COST + UNKNOWN_CHAR_COST * (i - j) as f64,
+                        false,
+                    ),
+                };
+                if best[j].0 + cost < best[i].0 {
+                    best[i] = (best[j].0 + cost, j, is_known);
+                }
+            }
+        }
+
+        let mut segments = vec![];
+        let mut i = n;
+        while i > 0 {
+            let (_, j, is_known) = best[i];
+            match segments.last_mut() {
+                // unknown runs longer than the longest word are split by the window, join them back
+                Some((start, _, false)) if !is_known && *start == i => *start = j,
+                _ => segments.push((j, i, is_known)),
+            }
+            i = j;
+        }
+        segments.reverse();
+
+        segments
+            .into_iter()
+            .map(|(j, i, is_known)| (&chunk[j..i], is_known))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChunkClass {
+    Letters,
+    Digits,
+    Other,
+}
+
+fn chunks(tag: &str) -> Vec<(&str, ChunkClass)> {
+    let class = |c: char| {
+        if c.is_alphabetic() {
+            ChunkClass::Letters
+        } else if c.is_ascii_digit() {
+            ChunkClass::Digits
+        } else {
+            ChunkClass::Other
+        }
+    };
+
+    let chars = tag.char_indices().collect::<Vec<_>>();
+    let mut chunks = vec![];
+    let mut start: Option<(usize, ChunkClass)> = None;
+    for (k, &(i, c)) in chars.iter().enumerate() {
+        if c == '_' {
+            if let Some((start, start_class)) = start.take() {
+                chunks.push((&tag[start..i], start_class));
+            }
+            continue;
+        }
+
+        match start {
+            Some((s, start_class)) => {
+                let prev = chars[k - 1].1;
+                let next = chars.get(k + 1).map(|x| x.1);
+                let boundary = start_class != class(c)
+                    || (c.is_uppercase() && prev.is_lowercase())
+                    || (c.is_uppercase()
+                        && prev.is_uppercase()
+                        && next.map(|x| x.is_lowercase()).unwrap_or(false));
+                if boundary {
+                    chunks.push((&tag[s..i], start_class));
+                    start = Some((i, class(c)));
+                }
+            }
+            None => start = Some((i, class(c))),
+        }
+    }
+    if let Some((start, start_class)) = start {
+        chunks.push((&tag[start..], start_class));
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(tag: &str) -> Vec<String> {
+        segment_hashtag(tag).words
+    }
+
+    #[test]
+    fn test_segment_hashtag() {
+        assert_eq!(words("#throwbacktuesday"), vec!["throwback", "tuesday"]);
+        assert_eq!(words("#ThrowbackTuesday"), vec!["Throwback", "Tuesday"]);
+        assert_eq!(segment_hashtag("#ThrowbackTuesday").confidence, 1.0);
+        assert_eq!(
+            words("#rustlangisawesome"),
+            vec!["rust", "lang", "is", "awesome"]
+        );
+        assert_eq!(words("#awesome"), vec!["awesome"]);
+        assert_eq!(words("#photooftheday"), vec!["photo", "of", "the", "day"]);
+        assert_eq!(words("#catsofinstagram"), vec!["cats", "of", "instagram"]);
+        assert_eq!(words("#selfietime"), vec!["selfie", "time"]);
+        assert_eq!(words("#goodmorningworld"), vec!["good", "morning", "world"]);
+        assert_eq!(words("GoodMorningWorld"), vec!["Good", "Morning", "World"]);
+        assert_eq!(segment_hashtag("GoodMorningWorld").confidence, 1.0);
+        assert_eq!(words("#musicislife"), vec!["music", "is", "life"]);
+        assert_eq!(words("#NASAPhotos"), vec!["NASA", "Photos"]);
+        assert_eq!(words("#ILoveNY"), vec!["I", "Love", "NY"]);
+        assert_eq!(words("#summer_vibes2021"), vec!["summer", "vibes", "2021"]);
+        assert_eq!(words("#CaféParis"), vec!["Café", "Paris"]);
+        assert_eq!(words("#東京tokyo"), vec!["東京tokyo"]);
+
+        let segmentation = segment_hashtag("#pictureoftheday");
+        assert_eq!(segmentation.words, vec!["picture", "of", "the", "day"]);
+        assert_eq!(segmentation.confidence, 1.0);
+
+        let segmentation = segment_hashtag("#lovezqxj");
+        assert_eq!(segmentation.words, vec!["love", "zqxj"]);
+        assert_eq!(segmentation.confidence, 0.5);
+
+        assert_eq!(segment_hashtag("#").words, Vec::<String>::new());
+        assert_eq!(segment_hashtag("#").confidence, 0.0);
+        assert_eq!(segment_hashtag("#_").words, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_segmenter_from_words() {
+        let segmenter = Segmenter::from_words(["Rust", "lang", "", "r"]);
+        let segmentation = segmenter.segment("rustlangzzz");
+        assert_eq!(segmentation.words, vec!["rust", "lang", "zzz"]);
+        assert!((segmentation.confidence - 8.0 / 11.0).abs() < 1e-9);
+
+        let segmentation = segmenter.segment(&"z".repeat(20));
+        assert_eq!(segmentation.words, vec!["z".repeat(20)]);
+        assert_eq!(segmentation.confidence, 0.0);
+
+        let segmenter = Segmenter::from_words(
+            ["a", "we", "some"]
+                .into_iter()
+                .map(str::to_owned)
+                .chain((0..1000).map(|i| format!("filler{}", i)))
+                .chain(["awesome".to_owned()]),
+        );
+        assert_eq!(segmenter.segment("awesome").words, vec!["awesome"]);
+    }
+}
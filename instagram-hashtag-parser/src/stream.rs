@@ -0,0 +1,389 @@
+//! Lazy hashtag extraction over caption dumps, one [`Record`] per caption.
+
+use core::fmt;
+#[cfg(feature = "with-csv")]
+use std::io::Read;
+use std::io::{self, BufRead};
+
+use crate::Backend;
+
+pub const DEFAULT_FIELD: &str = "caption";
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// Zero-based index of the record in the dump.
+    pub index: usize,
+    /// One-based line in the dump where the record starts.
+    pub line: u64,
+    pub hashtags: Vec<String>,
+}
+
+//
+#[derive(Debug)]
+pub enum StreamError {
+    IoError(io::Error),
+    #[cfg(feature = "with-csv")]
+    CsvError(csv::Error),
+    #[cfg(feature = "with-json")]
+    JsonError {
+        line: u64,
+        err: serde_json::Error,
+    },
+    FieldMissing {
+        line: u64,
+        field: String,
+    },
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+/// Plain text, one caption per line.
+#[derive(Debug)]
+pub struct LineRecords<R> {
+    reader: R,
+    backend: Backend,
+    buf: String,
+    index: usize,
+    line: u64,
+}
+
+impl<R: BufRead> LineRecords<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_backend(reader, Backend::default())
+    }
+
+    pub fn with_backend(reader: R, backend: Backend) -> Self {
+        Self {
+            reader,
+            backend,
+            buf: String::new(),
+            index: 0,
+            line: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for LineRecords<R> {
+    type Item = Result<Record, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buf.clear();
+        match self.reader.read_line(&mut self.buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                self.line += 1;
+                let record = Record {
+                    index: self.index,
+                    line: self.line,
+                    hashtags: self.backend.hashtags(&self.buf),
+                };
+                self.index += 1;
+                Some(Ok(record))
+            }
+            Err(err) => Some(Err(StreamError::IoError(err))),
+        }
+    }
+}
+
+/// CSV with a header row, captions are read from the `caption` column by default.
+#[cfg(feature = "with-csv")]
+#[derive(Debug)]
+pub struct CsvRecords<R> {
+    reader: csv::Reader<R>,
+    backend: Backend,
+    field: String,
+    column: Option<usize>,
+    record: csv::StringRecord,
+    index: usize,
+    done: bool,
+}
+
+#[cfg(feature = "with-csv")]
+impl<R: Read> CsvRecords<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_backend(reader, Backend::default())
+    }
+
+    pub fn with_backend(reader: R, backend: Backend) -> Self {
+        Self {
+            reader: csv::ReaderBuilder::new().flexible(true).from_reader(reader),
+            backend,
+            field: DEFAULT_FIELD.to_owned(),
+            column: None,
+            record: csv::StringRecord::new(),
+            index: 0,
+            done: false,
+        }
+    }
+
+    pub fn field(mut self, field: impl Into<String>) -> Self {
+        self.field = field.into();
+        self
+    }
+
+    fn column(&mut self) -> Result<usize, StreamError> {
+        if let Some(column) = self.column {
+            return Ok(column);
+        }
+
+        let column = self
+            .reader
+            .headers()
+            .map_err(StreamError::CsvError)?
+            .iter()
+            .position(|x| x == self.field)
+            .ok_or_else(|| StreamError::FieldMissing {
+                line: 1,
+                field: self.field.to_owned(),
+            })?;
+        self.column = Some(column);
+        Ok(column)
+    }
+}
+
+#[cfg(feature = "with-csv")]
+impl<R: Read> Iterator for CsvRecords<R> {
+    type Item = Result<Record, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let column = match self.column() {
+            Ok(column) => column,
+            Err(err) => {
+                self.done = true;
+                return Some(Err(err));
+            }
+        };
+
+        match self.reader.read_record(&mut self.record) {
+            Ok(false) => None,
+            Ok(true) => {
+                let line = self.record.position().map(|x| x.line()).unwrap_or(0);
+                let caption = match self.record.get(column) {
+                    Some(caption) => caption,
+                    None => {
+                        return Some(Err(StreamError::FieldMissing {
+                            line,
+                            field: self.field.to_owned(),
+                        }))
+                    }
+                };
+
+                let record = Record {
+                    index: self.index,
+                    line,
+                    hashtags: self.backend.hashtags(caption),
+                };
+                self.index += 1;
+                Some(Ok(record))
+            }
+            Err(err) => Some(Err(StreamError::CsvError(err))),
+        }
+    }
+}
+
+/// JSON Lines, captions are read from the `caption` field by default. Blank lines are skipped.
+#[cfg(feature = "with-json")]
+#[derive(Debug)]
+pub struct JsonlRecords<R> {
+    reader: R,
+    backend: Backend,
+    field: String,
+    buf: String,
+    index: usize,
+    line: u64,
+}
+
+#[cfg(feature = "with-json")]
+impl<R: BufRead> JsonlRecords<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_backend(reader, Backend::default())
+    }
+
+    pub fn with_backend(reader: R, backend: Backend) -> Self {
+        Self {
+            reader,
+            backend,
+            field: DEFAULT_FIELD.to_owned(),
+            buf: String::new(),
+            index: 0,
+            line: 0,
+        }
+    }
+
+    pub fn field(mut self, field: impl Into<String>) -> Self {
+        self.field = field.into();
+        self
+    }
+}
+
+#[cfg(feature = "with-json")]
+impl<R: BufRead> Iterator for JsonlRecords<R> {
+    type Item = Result<Record, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.buf.clear();
+            match self.reader.read_line(&mut self.buf) {
+                Ok(0) => return None,
+                Ok(_) => self.line += 1,
+                Err(err) => return Some(Err(StreamError::IoError(err))),
+            }
+            if self.buf.trim().is_empty() {
+                continue;
+            }
+
+            let line = self.line;
+            let value = match serde_json::from_str::<serde_json::Value>(&self.buf) {
+                Ok(value) => value,
+                Err(err) => return Some(Err(StreamError::JsonError { line, err })),
+            };
+            let caption = match value.get(&self.field) {
+                Some(serde_json::Value::String(caption)) => caption.as_str(),
+                Some(serde_json::Value::Null) => "",
+                _ => {
+                    return Some(Err(StreamError::FieldMissing {
+                        line,
+                        field: self.field.to_owned(),
+                    }))
+                }
+            };
+
+            let record = Record {
+                index: self.index,
+                line,
+                hashtags: self.backend.hashtags(caption),
+            };
+            self.index += 1;
+            return Some(Ok(record));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_line_records() -> Result<(), Box<dyn error::Error>> {
+        let records = LineRecords::new("#rust is #awesome\n\n#我#我的".as_bytes())
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            records,
+            vec![
+                Record {
+                    index: 0,
+                    line: 1,
+                    hashtags: vec!["rust".to_owned(), "awesome".to_owned()]
+                },
+                Record {
+                    index: 1,
+                    line: 2,
+                    hashtags: vec![]
+                },
+                Record {
+                    index: 2,
+                    line: 3,
+                    hashtags: vec!["我".to_owned(), "我的".to_owned()]
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-csv")]
+    #[test]
+    fn test_csv_records() -> Result<(), Box<dyn error::Error>> {
+        let data = include_str!("../tests/files/captions.csv.example");
+        let records = CsvRecords::new(data.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            records
+                .iter()
+                .map(|x| (x.index, x.line, x.hashtags.to_owned()))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, 2, vec!["rust".to_owned(), "awesome".to_owned()]),
+                (
+                    1,
+                    3,
+                    vec!["我".to_owned(), "我的".to_owned(), "我的小".to_owned()]
+                ),
+            ]
+        );
+
+        let data = "id,text\n1,#foo\n2,\"multi\nline #bar\"\n3,#baz\n";
+        let records = CsvRecords::new(data.as_bytes())
+            .field("text")
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            records
+                .iter()
+                .map(|x| (x.line, x.hashtags.to_owned()))
+                .collect::<Vec<_>>(),
+            vec![
+                (2, vec!["foo".to_owned()]),
+                (3, vec!["bar".to_owned()]),
+                (5, vec!["baz".to_owned()]),
+            ]
+        );
+
+        match CsvRecords::new("id\n1\n".as_bytes()).next() {
+            Some(Err(StreamError::FieldMissing { line: 1, field })) => {
+                assert_eq!(field, "caption")
+            }
+            x => panic!("{:?}", x),
+        }
+        let mut records = CsvRecords::new("id\n1\n".as_bytes());
+        assert!(records.next().is_some());
+        assert!(records.next().is_none());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-json")]
+    #[test]
+    fn test_jsonl_records() -> Result<(), Box<dyn error::Error>> {
+        let data = r##"{"id":1,"caption":"#rust is #awesome"}
+
+{"id":2,"caption":null}
+{"id":3,"caption":"我 #我的"}
+"##;
+        let records = JsonlRecords::new(data.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            records
+                .iter()
+                .map(|x| (x.index, x.line, x.hashtags.to_owned()))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, 1, vec!["rust".to_owned(), "awesome".to_owned()]),
+                (1, 3, vec![]),
+                (2, 4, vec!["我的".to_owned()]),
+            ]
+        );
+
+        let mut records = JsonlRecords::new("{\"text\":\"#foo\"}\nnot json\n".as_bytes());
+        assert!(matches!(
+            records.next(),
+            Some(Err(StreamError::FieldMissing { line: 1, .. }))
+        ));
+        assert!(matches!(
+            records.next(),
+            Some(Err(StreamError::JsonError { line: 2, .. }))
+        ));
+        assert!(records.next().is_none());
+
+        Ok(())
+    }
+}
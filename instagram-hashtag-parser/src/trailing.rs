@@ -0,0 +1,174 @@
+//! The "hashtag wall" at the end of a caption, usually set apart by dots or blank lines.
+
+use crate::{Backend, HashtagSpan};
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrailingHashtags<'a> {
+    /// The caption before the separators, without trailing whitespace.
+    pub body: &'a str,
+    /// The lines between the body and the block, without line breaks. Blank lines are `""`.
+    pub separators: Vec<&'a str>,
+    /// The block as written, without trailing whitespace.
+    pub block: &'a str,
+    /// The hashtags of the block, spans are relative to the caption.
+    pub hashtags: Vec<HashtagSpan>,
+}
+
+impl<'a> TrailingHashtags<'a> {
+    /// Byte offset of the block in the caption, hashtags before it are inline.
+    pub fn block_start(&self) -> usize {
+        self.hashtags.first().map(|x| x.span.start).unwrap_or(0)
+    }
+}
+
+pub fn split_trailing_hashtags(caption: &str) -> Option<TrailingHashtags<'_>> {
+    split_trailing_hashtags_with(caption, Backend::default())
+}
+
+/// Returns `None` when the caption doesn't end with lines made of hashtags only.
+pub fn split_trailing_hashtags_with(
+    caption: &str,
+    backend: Backend,
+) -> Option<TrailingHashtags<'_>> {
+    let hashtags = backend.hashtag_spans(caption);
+
+    let mut lines = vec![];
+    let mut start = 0;
+    for line in caption.split_inclusive('\n') {
+        let end = start + line.len();
+        let content = line.trim_end_matches(['\r', '\n']);
+        let kind = line_kind(content, start, &hashtags);
+        lines.push((start, content, kind));
+        start = end;
+    }
+
+    let mut i = lines.len();
+    while i > 0 && lines[i - 1].2 == LineKind::Blank {
+        i -= 1;
+    }
+
+    let mut block_line = None;
+    while i > 0 && matches!(lines[i - 1].2, LineKind::Hashtags | LineKind::Blank) {
+        if lines[i - 1].2 == LineKind::Hashtags {
+            block_line = Some(i - 1);
+        }
+        i -= 1;
+    }
+    let block_line = block_line?;
+
+    let mut separator_line = block_line;
+    while separator_line > 0
+        && matches!(
+            lines[separator_line - 1].2,
+            LineKind::Separator | LineKind::Blank
+        )
+    {
+        separator_line -= 1;
+    }
+
+    let block_start = lines[block_line].0;
+    let body_end = lines[separator_line].0;
+
+    Some(TrailingHashtags {
+        body: caption[..body_end].trim_end(),
+        separators: lines[separator_line..block_line]
+            .iter()
+            .map(|(_, content, _)| *content)
+            .collect(),
+        block: caption[block_start..].trim_end(),
+        hashtags: hashtags
+            .into_iter()
+            .filter(|x| x.span.start >= block_start)
+            .collect(),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Blank,
+    Separator,
+    Hashtags,
+    Text,
+}
+
+fn line_kind(content: &str, start: usize, hashtags: &[HashtagSpan]) -> LineKind {
+    if content.trim().is_empty() {
+        return LineKind::Blank;
+    }
+    if content
+        .chars()
+        .all(|c| c.is_whitespace() || is_separator(c))
+    {
+        return LineKind::Separator;
+    }
+
+    let end = start + content.len();
+    let mut rest = String::with_capacity(content.len());
+    let mut last = start;
+    for hashtag in hashtags
+        .iter()
+        .filter(|x| x.span.start >= start && x.span.start < end)
+    {
+        rest.push_str(&content[last - start..hashtag.span.start - start]);
+        last = hashtag.span.end.min(end);
+    }
+    if last == start {
+        return LineKind::Text;
+    }
+    rest.push_str(&content[last - start..]);
+
+    if rest.trim().is_empty() {
+        LineKind::Hashtags
+    } else {
+        LineKind::Text
+    }
+}
+
+fn is_separator(c: char) -> bool {
+    matches!(
+        c,
+        '.' | '·' | '•' | '…' | '-' | '_' | '–' | '—' | '*' | '~' | '|' | '⠀' | '\u{3164}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_trailing_hashtags() {
+        let caption = "Sunset at the #beach\n.\n.\n.\n#travel #sun\n\n#sea\n";
+        let trailing = split_trailing_hashtags(caption).unwrap();
+        assert_eq!(trailing.body, "Sunset at the #beach");
+        assert_eq!(trailing.separators, vec![".", ".", "."]);
+        assert_eq!(trailing.block, "#travel #sun\n\n#sea");
+        assert_eq!(
+            trailing
+                .hashtags
+                .iter()
+                .map(|x| x.text.as_str())
+                .collect::<Vec<_>>(),
+            vec!["travel", "sun", "sea"]
+        );
+        assert_eq!(trailing.block_start(), 27);
+
+        let trailing = split_trailing_hashtags("Hello\r\n\r\n•\r\n#a #b").unwrap();
+        assert_eq!(trailing.body, "Hello");
+        assert_eq!(trailing.separators, vec!["", "•"]);
+        assert_eq!(trailing.block, "#a #b");
+
+        let trailing = split_trailing_hashtags("Hello\n#a").unwrap();
+        assert_eq!(trailing.body, "Hello");
+        assert!(trailing.separators.is_empty());
+
+        let trailing = split_trailing_hashtags("#a #b").unwrap();
+        assert_eq!(trailing.body, "");
+        assert_eq!(trailing.block, "#a #b");
+
+        assert_eq!(split_trailing_hashtags("Sunset at the #beach #sun"), None);
+        assert_eq!(split_trailing_hashtags("#a\nand more text"), None);
+        assert_eq!(split_trailing_hashtags("\n.\n"), None);
+        assert_eq!(split_trailing_hashtags(""), None);
+    }
+}
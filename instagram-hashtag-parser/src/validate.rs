@@ -0,0 +1,213 @@
+use core::ops::Range;
+
+use crate::{mentions, Backend};
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptionLimits {
+    pub max_hashtags: usize,
+    pub max_mentions: usize,
+    /// Counted in chars.
+    pub max_chars: usize,
+}
+
+/// Ref https://developers.facebook.com/docs/instagram-api/reference/ig-user/media
+impl Default for CaptionLimits {
+    fn default() -> Self {
+        Self {
+            max_hashtags: 30,
+            max_mentions: 20,
+            max_chars: 2200,
+        }
+    }
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub kind: ViolationKind,
+    /// Byte range in the caption.
+    pub span: Range<usize>,
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// One per hashtag over the limit, `count` is the total.
+    TooManyHashtags {
+        count: usize,
+        max: usize,
+    },
+    /// One per mention over the limit, `count` is the total.
+    TooManyMentions {
+        count: usize,
+        max: usize,
+    },
+    /// The span covers the chars over the limit.
+    TooLong {
+        chars: usize,
+        max: usize,
+    },
+    BannedCharacter(char),
+}
+
+pub fn validate_caption(caption: &str) -> Vec<Violation> {
+    validate_caption_with(caption, &CaptionLimits::default(), Backend::default())
+}
+
+/// Violations are ordered by kind, then by position.
+pub fn validate_caption_with(
+    caption: &str,
+    limits: &CaptionLimits,
+    backend: Backend,
+) -> Vec<Violation> {
+    let mut violations = vec![];
+
+    let hashtags = backend.hashtag_spans(caption);
+    if hashtags.len() > limits.max_hashtags {
+        let kind = ViolationKind::TooManyHashtags {
+            count: hashtags.len(),
+            max: limits.max_hashtags,
+        };
+        violations.extend(
+            hashtags
+                .into_iter()
+                .skip(limits.max_hashtags)
+                .map(|x| Violation {
+                    kind: kind.to_owned(),
+                    span: x.span,
+                }),
+        );
+    }
+
+    let mentions = mentions(caption);
+    if mentions.len() > limits.max_mentions {
+        let kind = ViolationKind::TooManyMentions {
+            count: mentions.len(),
+            max: limits.max_mentions,
+        };
+        violations.extend(
+            mentions
+                .into_iter()
+                .skip(limits.max_mentions)
+                .map(|x| Violation {
+                    kind: kind.to_owned(),
+                    span: x.span,
+                }),
+        );
+    }
+
+    let chars = caption.chars().count();
+    if chars > limits.max_chars {
+        let start = caption
+            .char_indices()
+            .nth(limits.max_chars)
+            .map(|(i, _)| i)
+            .unwrap_or(caption.len());
+        violations.push(Violation {
+            kind: ViolationKind::TooLong {
+                chars,
+                max: limits.max_chars,
+            },
+            span: start..caption.len(),
+        });
+    }
+
+    violations.extend(
+        caption
+            .char_indices()
+            .filter(|(_, c)| is_banned(*c))
+            .map(|(i, c)| Violation {
+                kind: ViolationKind::BannedCharacter(c),
+                span: i..i + c.len_utf8(),
+            }),
+    );
+
+    violations
+}
+
+/// Control chars other than `\t`, `\n` and `\r`, the replacement char and noncharacters.
+pub(crate) fn is_banned(c: char) -> bool {
+    (c.is_control() && !matches!(c, '\t' | '\n' | '\r'))
+        || c == '\u{FFFD}'
+        || matches!(c, '\u{FDD0}'..='\u{FDEF}')
+        || (c as u32) & 0xFFFE == 0xFFFE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_caption() {
+        assert!(validate_caption("#rust is #awesome @foo").is_empty());
+
+        let caption = (0..32).map(|i| format!("#t{} ", i)).collect::<String>();
+        assert_eq!(
+            validate_caption(&caption),
+            vec![
+                Violation {
+                    kind: ViolationKind::TooManyHashtags { count: 32, max: 30 },
+                    span: 140..144
+                },
+                Violation {
+                    kind: ViolationKind::TooManyHashtags { count: 32, max: 30 },
+                    span: 145..149
+                },
+            ]
+        );
+        assert_eq!(&caption[140..144], "#t30");
+
+        let caption = (0..21).map(|i| format!("@u{} ", i)).collect::<String>();
+        assert_eq!(
+            validate_caption(&caption),
+            vec![Violation {
+                kind: ViolationKind::TooManyMentions { count: 21, max: 20 },
+                span: 90..94
+            }]
+        );
+
+        let caption = "我".repeat(2201);
+        assert_eq!(
+            validate_caption(&caption),
+            vec![Violation {
+                kind: ViolationKind::TooLong {
+                    chars: 2201,
+                    max: 2200
+                },
+                span: 6600..6603
+            }]
+        );
+        assert!(validate_caption(&"我".repeat(2200)).is_empty());
+
+        assert_eq!(
+            validate_caption("a\u{0}b\nc\u{FFFF}"),
+            vec![
+                Violation {
+                    kind: ViolationKind::BannedCharacter('\u{0}'),
+                    span: 1..2
+                },
+                Violation {
+                    kind: ViolationKind::BannedCharacter('\u{FFFF}'),
+                    span: 5..8
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_caption_with() {
+        let limits = CaptionLimits {
+            max_hashtags: 1,
+            max_mentions: 0,
+            max_chars: 10,
+        };
+        assert_eq!(
+            validate_caption_with("#a #b @c", &limits, Backend::default())
+                .into_iter()
+                .map(|x| x.span)
+                .collect::<Vec<_>>(),
+            vec![3..5, 6..8]
+        );
+    }
+}
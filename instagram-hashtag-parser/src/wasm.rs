@@ -0,0 +1,186 @@
+//! `wasm-bindgen` exports for the web.
+//!
+//! Offsets are UTF-16 code units, like JS string indexes, so they can be passed to `String.prototype.slice`.
+//! Build with `--no-default-features --features with-wasm` to get the `Regex` backend without the `hashtag` crate.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{validate_caption_with, Backend, CaptionLimits, ViolationKind};
+
+//
+#[wasm_bindgen(js_name = HashtagSpan)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsHashtagSpan {
+    text: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+#[wasm_bindgen(js_class = HashtagSpan)]
+impl JsHashtagSpan {
+    /// The tag without the leading `#`.
+    #[wasm_bindgen(getter)]
+    pub fn text(&self) -> String {
+        self.text.to_owned()
+    }
+}
+
+//
+#[wasm_bindgen(js_name = Violation)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsViolation {
+    kind: String,
+    pub start: u32,
+    pub end: u32,
+    /// The total for `tooManyHashtags` and `tooManyMentions`, the chars for `tooLong`.
+    pub count: Option<u32>,
+    pub max: Option<u32>,
+    character: Option<String>,
+}
+
+#[wasm_bindgen(js_class = Violation)]
+impl JsViolation {
+    /// One of `tooManyHashtags`, `tooManyMentions`, `tooLong` and `bannedCharacter`.
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> String {
+        self.kind.to_owned()
+    }
+
+    /// Only for `bannedCharacter`.
+    #[wasm_bindgen(getter)]
+    pub fn character(&self) -> Option<String> {
+        self.character.to_owned()
+    }
+}
+
+#[wasm_bindgen(js_name = hashtags)]
+pub fn js_hashtags(caption: &str) -> Vec<String> {
+    Backend::default().hashtags(caption)
+}
+
+#[wasm_bindgen(js_name = hashtagSpans)]
+pub fn js_hashtag_spans(caption: &str) -> Vec<JsHashtagSpan> {
+    let offsets = utf16_offsets(caption);
+    Backend::default()
+        .hashtag_spans(caption)
+        .into_iter()
+        .map(|x| JsHashtagSpan {
+            text: x.text,
+            start: offsets[x.span.start],
+            end: offsets[x.span.end],
+        })
+        .collect()
+}
+
+/// Limits that are not set fall back to [`CaptionLimits::default`], `maxChars` is counted in code points.
+#[wasm_bindgen(js_name = validateCaption)]
+pub fn js_validate_caption(
+    caption: &str,
+    max_hashtags: Option<u32>,
+    max_mentions: Option<u32>,
+    max_chars: Option<u32>,
+) -> Vec<JsViolation> {
+    let default = CaptionLimits::default();
+    let limits = CaptionLimits {
+        max_hashtags: max_hashtags
+            .map(|x| x as usize)
+            .unwrap_or(default.max_hashtags),
+        max_mentions: max_mentions
+            .map(|x| x as usize)
+            .unwrap_or(default.max_mentions),
+        max_chars: max_chars.map(|x| x as usize).unwrap_or(default.max_chars),
+    };
+
+    let offsets = utf16_offsets(caption);
+    validate_caption_with(caption, &limits, Backend::default())
+        .into_iter()
+        .map(|x| {
+            let (kind, count, max, character) = match x.kind {
+                ViolationKind::TooManyHashtags { count, max } => {
+                    ("tooManyHashtags", Some(count), Some(max), None)
+                }
+                ViolationKind::TooManyMentions { count, max } => {
+                    ("tooManyMentions", Some(count), Some(max), None)
+                }
+                ViolationKind::TooLong { chars, max } => ("tooLong", Some(chars), Some(max), None),
+                ViolationKind::BannedCharacter(c) => {
+                    ("bannedCharacter", None, None, Some(c.to_string()))
+                }
+            };
+            JsViolation {
+                kind: kind.to_owned(),
+                start: offsets[x.span.start],
+                end: offsets[x.span.end],
+                count: count.map(|x| x as u32),
+                max: max.map(|x| x as u32),
+                character,
+            }
+        })
+        .collect()
+}
+
+/// UTF-16 offset of every byte offset, only char boundaries are meaningful.
+fn utf16_offsets(s: &str) -> Vec<u32> {
+    let mut offsets = vec![0; s.len() + 1];
+    let mut offset = 0;
+    for (i, c) in s.char_indices() {
+        offsets[i..i + c.len_utf8()].fill(offset);
+        offset += c.len_utf16() as u32;
+    }
+    offsets[s.len()] = offset;
+    offsets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_js_hashtag_spans() {
+        assert_eq!(js_hashtags("😀 #rust #我"), vec!["rust", "我"]);
+
+        let spans = js_hashtag_spans("😀 #rust #我");
+        assert_eq!(
+            spans
+                .iter()
+                .map(|x| (x.text(), x.start, x.end))
+                .collect::<Vec<_>>(),
+            vec![("rust".to_owned(), 3, 8), ("我".to_owned(), 9, 11)]
+        );
+    }
+
+    #[test]
+    fn test_js_validate_caption() {
+        assert!(js_validate_caption("#rust is #awesome", None, None, None).is_empty());
+
+        assert_eq!(
+            js_validate_caption("😀 #a #b \u{0}", Some(1), None, Some(5)),
+            vec![
+                JsViolation {
+                    kind: "tooManyHashtags".to_owned(),
+                    start: 6,
+                    end: 8,
+                    count: Some(2),
+                    max: Some(1),
+                    character: None,
+                },
+                JsViolation {
+                    kind: "tooLong".to_owned(),
+                    start: 6,
+                    end: 10,
+                    count: Some(9),
+                    max: Some(5),
+                    character: None,
+                },
+                JsViolation {
+                    kind: "bannedCharacter".to_owned(),
+                    start: 9,
+                    end: 10,
+                    count: None,
+                    max: None,
+                    character: Some("\u{0}".to_owned()),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use core::ops::Range;
+
+use crate::{HostPolicy, MediaLink, MediaLinkParseError, LENIENT_HOSTS};
+
+//
+#[derive(Debug, PartialEq)]
+pub struct FoundLink {
+    /// Byte range in the text.
+    pub span: Range<usize>,
+    pub link: Result<MediaLink, MediaLinkParseError>,
+}
+
+/// Finds Instagram links in free text, with or without a scheme, see [`HostPolicy::Lenient`].
+///
+/// Trailing punctuation, including non ASCII punctuation and symbols, and closing brackets without an opening one in the link,
+/// are not part of the link, so `(see instagram.com/p/xyz).` gives `instagram.com/p/xyz`.
+/// A link also ends where the next one starts and at `](`, as in `[instagram.com/p/xyz](https://instagram.com/p/xyz)`.
+pub fn find_links(text: &str) -> Vec<FoundLink> {
+    let lower = text.to_ascii_lowercase();
+
+    let mut links = vec![];
+    let mut token_start = 0;
+    for token in lower.split_inclusive(is_delimiter) {
+        let token_end = token_start + token.len();
+        let token = token.trim_end_matches(is_delimiter);
+
+        let starts = link_starts(token);
+        for (k, &start) in starts.iter().enumerate() {
+            let next = starts.get(k + 1).copied().unwrap_or(token.len());
+            let end = token[start..next]
+                .find("](")
+                .map(|x| start + x)
+                .unwrap_or(next);
+
+            let start = token_start + start;
+            let end = trim_end(text, start, token_start + end);
+            if end > start {
+                let s = &text[start..end];
+                let url = if s.contains("://") {
+                    s.to_owned()
+                } else {
+                    format!("https://{}", s)
+                };
+                links.push(FoundLink {
+                    span: start..end,
+                    link: MediaLink::parse_with_policy(url, &HostPolicy::Lenient).map(|x| x.link),
+                });
+            }
+        }
+
+        token_start = token_end;
+    }
+
+    links
+}
+
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '<' | '>' | '"' | '`')
+}
+
+/// Offsets of the scheme, or of the host when there is no scheme, in order.
+///
+/// Links in a query value, like the `u` of `l.instagram.com/?u=https://...`, belong to the link before them.
+fn link_starts(token: &str) -> Vec<usize> {
+    let hosts = LENIENT_HOSTS
+        .iter()
+        .copied()
+        .chain(core::iter::once("l.instagram.com"));
+
+    let mut starts = hosts
+        .flat_map(|host| {
+            token
+                .match_indices(host)
+                .filter(move |(i, _)| {
+                    let before = token[..*i].chars().next_back();
+                    let after = token[i + host.len()..].chars().next();
+                    !before
+                        .map(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+                        .unwrap_or(false)
+                        && matches!(after, None | Some('/') | Some('?') | Some(':'))
+                })
+                .map(|(i, _)| i)
+        })
+        .map(|i| {
+            ["https://", "http://"]
+                .iter()
+                .find(|x| token[..i].ends_with(*x))
+                .map(|x| i - x.len())
+                .unwrap_or(i)
+        })
+        .filter(|i| !token[..*i].ends_with('='))
+        .collect::<Vec<_>>();
+    starts.sort_unstable();
+    starts.dedup();
+    starts
+}
+
+fn trim_end(text: &str, start: usize, mut end: usize) -> usize {
+    while end > start {
+        let s = &text[start..end];
+        let c = s.chars().next_back().unwrap_or_default();
+        let trim = match c {
+            '.' | ',' | ';' | ':' | '!' | '?' | '\'' | '*' | '~' => true,
+            ')' => s.matches('(').count() < s.matches(')').count(),
+            ']' => s.matches('[').count() < s.matches(']').count(),
+            '}' => s.matches('{').count() < s.matches('}').count(),
+            // e.g. `。` and `，`
+            c => !c.is_ascii() && !c.is_alphanumeric(),
+        };
+        if !trim {
+            break;
+        }
+        end -= c.len_utf8();
+    }
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::MediaMetadata;
+
+    fn spans(text: &str) -> Vec<&str> {
+        find_links(text)
+            .into_iter()
+            .map(|x| &text[x.span])
+            .collect()
+    }
+
+    #[test]
+    fn test_find_links() {
+        let text = "Look at instagram.com/p/CJBsZ11MYha, and (https://www.instagram.com/reel/CH-__hxDV7T/).";
+        let links = find_links(text);
+        assert_eq!(links.len(), 2);
+        assert_eq!(
+            &text[links[0].span.to_owned()],
+            "instagram.com/p/CJBsZ11MYha"
+        );
+        assert_eq!(
+            links[0].link,
+            Ok(MediaLink::Post {
+                metadata: MediaMetadata::with_shortcode("CJBsZ11MYha".to_owned()).unwrap()
+            })
+        );
+        assert_eq!(
+            &text[links[1].span.to_owned()],
+            "https://www.instagram.com/reel/CH-__hxDV7T/"
+        );
+
+        assert_eq!(
+            spans("<https://instagr.am/p/CJBsZ11MYha/> [post](http://m.instagram.com/p/CJBsZ11MYha/)!"),
+            vec![
+                "https://instagr.am/p/CJBsZ11MYha/",
+                "http://m.instagram.com/p/CJBsZ11MYha/"
+            ]
+        );
+        assert_eq!(
+            spans("链接：INSTAGRAM.COM/p/CJBsZ11MYha。"),
+            vec!["INSTAGRAM.COM/p/CJBsZ11MYha"]
+        );
+        assert_eq!(
+            spans("[instagram.com/p/CJBsZ11MYha](https://instagram.com/p/CJBsZ11MYha)"),
+            vec![
+                "instagram.com/p/CJBsZ11MYha",
+                "https://instagram.com/p/CJBsZ11MYha"
+            ]
+        );
+        assert_eq!(
+            spans("instagram.com/p/CJBsZ11MYha,instagram.com/reel/CH-__hxDV7T"),
+            vec![
+                "instagram.com/p/CJBsZ11MYha",
+                "instagram.com/reel/CH-__hxDV7T"
+            ]
+        );
+        assert!(
+            find_links("[instagram.com/p/CJBsZ11MYha](https://instagram.com/p/CJBsZ11MYha)")
+                .iter()
+                .all(|x| x.link.is_ok())
+        );
+        assert_eq!(
+            spans("https://l.instagram.com/?u=https://www.instagram.com/p/CJBsZ11MYha/&e=ATM"),
+            vec!["https://l.instagram.com/?u=https://www.instagram.com/p/CJBsZ11MYha/&e=ATM"]
+        );
+        assert_eq!(
+            spans("wiki https://en.wikipedia.org/wiki/Instagram_(app) and notinstagram.com/p/x"),
+            Vec::<&str>::new()
+        );
+    }
+
+    #[test]
+    fn test_find_links_error() {
+        let links =
+            find_links("profile: instagram.com/foo/ and ig.me/p/not-a-shortcode-at-all/!!!");
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].link, Err(MediaLinkParseError::Unsupported));
+        assert_eq!(
+            links[1].link,
+            Err(MediaLinkParseError::Invalid("shortcode invalid".to_owned()))
+        );
+
+        let links = find_links("reels: instagram.com/foo/reels/");
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].link, Err(MediaLinkParseError::Unsupported));
+    }
+}
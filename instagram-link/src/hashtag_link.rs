@@ -0,0 +1,208 @@
+use core::fmt;
+
+use url::{ParseError, Url};
+
+use crate::{HostPolicy, InstagramLink, MediaLinkParseError, ParsedLink};
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashtagLink {
+    /// The tag without the leading `#`, percent-decoded.
+    pub tag: String,
+}
+
+impl HashtagLink {
+    /// Parses `https://www.instagram.com/explore/tags/<tag>/` and `instagram://tag?name=<tag>`.
+    ///
+    /// Web links go through [`InstagramLink::parse`].
+    pub fn parse(url: impl AsRef<str>) -> Result<Self, HashtagLinkParseError> {
+        let url = url.as_ref();
+
+        if url.starts_with("instagram:") {
+            return Self::parse_deep_link(url);
+        }
+
+        Self::from_instagram_link(InstagramLink::parse(url)?)
+    }
+
+    pub fn parse_with_policy(
+        url: impl AsRef<str>,
+        policy: &HostPolicy,
+    ) -> Result<ParsedLink<Self>, HashtagLinkParseError> {
+        let ParsedLink {
+            link,
+            scheme,
+            host,
+            redirects,
+            share,
+            owner_username,
+        } = InstagramLink::parse_with_policy(url, policy)?;
+
+        Ok(ParsedLink {
+            link: Self::from_instagram_link(link)?,
+            scheme,
+            host,
+            redirects,
+            share,
+            owner_username,
+        })
+    }
+
+    fn from_instagram_link(link: InstagramLink) -> Result<Self, HashtagLinkParseError> {
+        match link {
+            InstagramLink::Tag { tag } => Ok(Self { tag }),
+            _ => Err(HashtagLinkParseError::Unsupported),
+        }
+    }
+
+    fn parse_deep_link(url: &str) -> Result<Self, HashtagLinkParseError> {
+        let url = Url::parse(url).map_err(HashtagLinkParseError::UrlParseError)?;
+        if url.host_str() != Some("tag") {
+            return Err(HashtagLinkParseError::Unsupported);
+        }
+
+        let tag = url
+            .query_pairs()
+            .find(|(k, _)| k == "name")
+            .map(|(_, v)| v.into_owned())
+            .unwrap_or_default();
+        let tag = tag.strip_prefix('#').unwrap_or(&tag);
+        if tag.is_empty() {
+            return Err(HashtagLinkParseError::Invalid("tag not found".to_owned()));
+        }
+
+        Ok(Self {
+            tag: tag.to_owned(),
+        })
+    }
+}
+
+//
+#[derive(Debug, PartialEq)]
+pub enum HashtagLinkParseError {
+    UrlParseError(ParseError),
+    Invalid(String),
+    Unsupported,
+}
+
+impl fmt::Display for HashtagLinkParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for HashtagLinkParseError {}
+
+impl From<MediaLinkParseError> for HashtagLinkParseError {
+    fn from(err: MediaLinkParseError) -> Self {
+        match err {
+            MediaLinkParseError::UrlParseError(err) => Self::UrlParseError(err),
+            MediaLinkParseError::Invalid(s) => Self::Invalid(s),
+            MediaLinkParseError::Unsupported => Self::Unsupported,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_parse() -> Result<(), Box<dyn error::Error>> {
+        for url in [
+            "https://www.instagram.com/explore/tags/rust/",
+            "https://instagram.com/explore/tags/rust?hl=en",
+            "https://l.instagram.com/?u=https%3A%2F%2Fwww.instagram.com%2Fexplore%2Ftags%2Frust%2F",
+            "instagram://tag?name=rust",
+            "instagram://tag?name=%23rust",
+        ] {
+            assert_eq!(
+                HashtagLink::parse(url)?,
+                HashtagLink {
+                    tag: "rust".to_owned()
+                }
+            );
+        }
+
+        assert_eq!(
+            HashtagLink::parse(
+                "https://www.instagram.com/explore/tags/%E6%9D%B1%E4%BA%AC%E3%82%BF%E3%83%AF%E3%83%BC/"
+            )?
+            .tag,
+            "東京タワー"
+        );
+        assert_eq!(
+            HashtagLink::parse("instagram://tag?name=caf%C3%A9")?.tag,
+            "café"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_with_policy() -> Result<(), Box<dyn error::Error>> {
+        let parsed = HashtagLink::parse_with_policy(
+            "https://m.instagram.com/explore/tags/rust/",
+            &HostPolicy::Lenient,
+        )?;
+        assert_eq!(parsed.link.tag, "rust");
+        assert_eq!(parsed.host, "m.instagram.com");
+
+        let parsed = HashtagLink::parse_with_policy(
+            "http://www.instagram.com/explore/tags/rust/",
+            &HostPolicy::Lenient,
+        )?;
+        assert_eq!(parsed.link.tag, "rust");
+        assert!(parsed.is_upgraded());
+
+        assert!(HashtagLink::parse_with_policy(
+            "https://m.instagram.com/explore/tags/rust/",
+            &HostPolicy::Strict
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            HashtagLink::parse("https://www.instagram.com/explore/tags/"),
+            Err(HashtagLinkParseError::Unsupported)
+        );
+        assert_eq!(
+            HashtagLink::parse("https://www.instagram.com/explore/tags/%23/"),
+            Err(HashtagLinkParseError::Invalid("tag not found".to_owned()))
+        );
+        assert_eq!(
+            HashtagLink::parse("https://www.instagram.com/explore/locations/1/"),
+            Err(HashtagLinkParseError::Unsupported)
+        );
+        assert_eq!(
+            HashtagLink::parse("https://www.instagram.com/explore/tags/rust/top/"),
+            Err(HashtagLinkParseError::Unsupported)
+        );
+        assert_eq!(
+            HashtagLink::parse("https://example.com/explore/tags/rust/"),
+            Err(HashtagLinkParseError::Invalid("host mismatch".to_owned()))
+        );
+        assert_eq!(
+            HashtagLink::parse("http://www.instagram.com/explore/tags/rust/"),
+            Err(HashtagLinkParseError::Invalid("scheme mismatch".to_owned()))
+        );
+        assert_eq!(
+            HashtagLink::parse("https://www.instagram.com/rust/"),
+            Err(HashtagLinkParseError::Unsupported)
+        );
+        assert_eq!(
+            HashtagLink::parse("instagram://tag?name="),
+            Err(HashtagLinkParseError::Invalid("tag not found".to_owned()))
+        );
+        assert_eq!(
+            HashtagLink::parse("instagram://user?username=rust"),
+            Err(HashtagLinkParseError::Unsupported)
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use url::Url;
+
+use crate::{is_valid_username, MediaLinkParseError, ShareContext, RESERVED_PATHS};
+
+pub const STRICT_HOSTS: &[&str] = &["www.instagram.com", "instagram.com"];
+pub const LENIENT_HOSTS: &[&str] = &[
+    "www.instagram.com",
+    "instagram.com",
+    "m.instagram.com",
+    "instagr.am",
+    "www.instagr.am",
+    "ig.me",
+];
+
+//
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum HostPolicy {
+    /// `https` and [`STRICT_HOSTS`] only.
+    #[default]
+    Strict,
+    /// `http` or `https`, [`LENIENT_HOSTS`] in any case and with a trailing dot.
+    Lenient,
+    /// `http` or `https`, the hosts in any case and with a trailing dot.
+    Custom(Vec<String>),
+}
+
+impl HostPolicy {
+    pub(crate) fn check(&self, url: &Url) -> Result<(), MediaLinkParseError> {
+        let scheme_ok = match self {
+            Self::Strict => url.scheme() == "https",
+            Self::Lenient | Self::Custom(_) => matches!(url.scheme(), "https" | "http"),
+        };
+        if !scheme_ok {
+            return Err(MediaLinkParseError::Invalid("scheme mismatch".to_owned()));
+        }
+
+        let host = url.host_str().unwrap_or_default();
+        let host_ok = match self {
+            Self::Strict => STRICT_HOSTS.contains(&host),
+            Self::Lenient => LENIENT_HOSTS.contains(&normalize_host(host).as_str()),
+            Self::Custom(hosts) => {
+                let host = normalize_host(host);
+                hosts.iter().any(|x| normalize_host(x) == host)
+            }
+        };
+        if !host_ok {
+            return Err(MediaLinkParseError::Invalid("host mismatch".to_owned()));
+        }
+
+        Ok(())
+    }
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedLink<T> {
+    pub link: T,
+    /// As seen in the input, e.g. `http`, the link itself is always `https`.
+    pub scheme: String,
+    /// As seen in the input, lowercased, e.g. `instagr.am` or `instagram.com.`.
+    pub host: String,
+    /// See [`unwrap_redirects`](crate::unwrap_redirects), `scheme` and `host` are of the unwrapped URL.
+    pub redirects: Vec<String>,
+    /// From the query of the unwrapped URL.
+    pub share: Option<ShareContext>,
+    /// The `<username>` of `/<username>/p/<shortcode>/` and `/<username>/reel/<shortcode>/` links.
+    pub owner_username: Option<String>,
+}
+
+impl<T> ParsedLink<T> {
+    pub(crate) fn new(link: T, url: &Url, redirects: Vec<String>) -> Self {
+        Self {
+            link,
+            scheme: url.scheme().to_owned(),
+            host: url.host_str().unwrap_or_default().to_owned(),
+            redirects,
+            share: ShareContext::from_url(url),
+            owner_username: owner_username(url),
+        }
+    }
+
+    /// The scheme was `http`.
+    pub fn is_upgraded(&self) -> bool {
+        self.scheme != "https"
+    }
+}
+
+fn owner_username(url: &Url) -> Option<String> {
+    let segments = url
+        .path_segments()?
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<_>>();
+    match segments.as_slice() {
+        [username, "p" | "reel" | "reels", _, ..]
+            if !RESERVED_PATHS.contains(username) && is_valid_username(username) =>
+        {
+            Some((*username).to_owned())
+        }
+        _ => None,
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_check() -> Result<(), Box<dyn error::Error>> {
+        let url = Url::parse("http://M.Instagram.COM./p/CJBsZ11MYha/")?;
+        assert_eq!(
+            HostPolicy::Strict.check(&url),
+            Err(MediaLinkParseError::Invalid("scheme mismatch".to_owned()))
+        );
+        assert_eq!(HostPolicy::Lenient.check(&url), Ok(()));
+        assert_eq!(
+            HostPolicy::Custom(vec!["m.instagram.com".to_owned()]).check(&url),
+            Ok(())
+        );
+        assert_eq!(
+            HostPolicy::Custom(vec!["instagram.com".to_owned()]).check(&url),
+            Err(MediaLinkParseError::Invalid("host mismatch".to_owned()))
+        );
+
+        let url = Url::parse("https://instagram.com./p/CJBsZ11MYha/")?;
+        assert_eq!(
+            HostPolicy::Strict.check(&url),
+            Err(MediaLinkParseError::Invalid("host mismatch".to_owned()))
+        );
+
+        assert_eq!(
+            HostPolicy::Lenient.check(&Url::parse("ftp://instagram.com/p/CJBsZ11MYha/")?),
+            Err(MediaLinkParseError::Invalid("scheme mismatch".to_owned()))
+        );
+
+        Ok(())
+    }
+}
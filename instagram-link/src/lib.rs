@@ -5,6 +5,26 @@ use url::{ParseError, Url};
 mod media_metadata;
 pub use media_metadata::MediaMetadata;
 
+mod hashtag_link;
+pub use hashtag_link::{HashtagLink, HashtagLinkParseError};
+
+mod host_policy;
+pub use host_policy::{HostPolicy, ParsedLink, LENIENT_HOSTS, STRICT_HOSTS};
+
+mod redirect;
+pub use redirect::{unwrap_redirects, Unwrapped, MAX_REDIRECTS};
+
+mod find;
+pub use find::{find_links, FoundLink};
+
+mod share;
+pub use share::{
+    strip_share_ids, ShareContext, ShareId, ShareIdFormat, ShareIdParam, SHARE_ID_PARAMS,
+};
+
+mod link;
+pub use link::{is_valid_username, InstagramLink, ProfileTab, RESERVED_PATHS, USERNAME_MAX_LEN};
+
 //
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MediaLink {
@@ -28,33 +48,62 @@ pub enum MediaLink {
 }
 
 impl MediaLink {
+    /// Drops the owner username of `/<username>/p/<shortcode>/` links,
+    /// see [`parse_with_owner_username`](Self::parse_with_owner_username).
     pub fn parse(url: impl AsRef<str>) -> Result<Self, MediaLinkParseError> {
-        let url = url.as_ref();
-        let url = Url::parse(url).map_err(MediaLinkParseError::UrlParseError)?;
+        Self::parse_with_policy(url, &HostPolicy::Strict).map(|x| x.link)
+    }
 
-        if url.scheme() != "https" {
-            return Err(MediaLinkParseError::Invalid("scheme mismatch".to_owned()));
-        }
+    /// Like [`parse`](Self::parse), also returns the username of `/<username>/p/<shortcode>/`
+    /// and `/<username>/reel/<shortcode>/` links.
+    pub fn parse_with_owner_username(
+        url: impl AsRef<str>,
+    ) -> Result<(Self, Option<String>), MediaLinkParseError> {
+        Self::parse_with_policy(url, &HostPolicy::Strict).map(|x| (x.link, x.owner_username))
+    }
 
-        if url.host_str() != Some("www.instagram.com") && url.host_str() != Some("instagram.com") {
-            return Err(MediaLinkParseError::Invalid("host mismatch".to_owned()));
-        }
+    pub fn parse_with_policy(
+        url: impl AsRef<str>,
+        policy: &HostPolicy,
+    ) -> Result<ParsedLink<Self>, MediaLinkParseError> {
+        let Unwrapped { url, redirects } = unwrap_redirects(url)?;
+
+        policy.check(&url)?;
+
+        Self::parse_url(&url).map(|x| ParsedLink::new(x, &url, redirects))
+    }
 
+    pub(crate) fn parse_url(url: &Url) -> Result<Self, MediaLinkParseError> {
         let mut s = url.path().to_owned();
         s.remove(0);
-        let offset = s
-            .find(|c: char| c == '/')
-            .ok_or(MediaLinkParseError::Unsupported)?;
-        let r#type: String = s.drain(..offset).collect();
+        let offset = s.find('/').ok_or(MediaLinkParseError::Unsupported)?;
+        let mut r#type: String = s.drain(..offset).collect();
+
+        // the owner is in `ParsedLink::owner_username`
+        if !RESERVED_PATHS.contains(&r#type.as_str()) && is_valid_username(&r#type) {
+            s.remove(0);
+            let offset = s.find('/').ok_or(MediaLinkParseError::Unsupported)?;
+            r#type = s.drain(..offset).collect();
+            // `/<username>/reels/` is the profile tab
+            let shortcode = s.get(1..).and_then(|x| x.split('/').next());
+            if !matches!(r#type.as_str(), "p" | "reel" | "reels")
+                || shortcode.unwrap_or_default().is_empty()
+            {
+                return Err(MediaLinkParseError::Unsupported);
+            }
+        }
 
         match r#type.as_str() {
-            "p" | "tv" | "reel" => {
+            "p" | "tv" | "reel" | "reels" => {
                 s.remove(0);
-                let shortcode = if let Some(offset) = s.find(|c: char| c == '/') {
+                let shortcode = if let Some(offset) = s.find('/') {
                     s.drain(..offset).collect::<String>()
                 } else {
                     s.to_owned()
                 };
+                if shortcode.is_empty() || (r#type == "reels" && shortcode == "audio") {
+                    return Err(MediaLinkParseError::Unsupported);
+                }
 
                 let metadata = MediaMetadata::with_shortcode(shortcode)
                     .map_err(|_| MediaLinkParseError::Invalid("shortcode invalid".to_owned()))?;
@@ -62,19 +111,19 @@ impl MediaLink {
                 match r#type.as_str() {
                     "p" => Ok(Self::Post { metadata }),
                     "tv" => Ok(Self::IGTVVideo { metadata }),
-                    "reel" => Ok(Self::Reel { metadata }),
+                    "reel" | "reels" => Ok(Self::Reel { metadata }),
                     _ => unreachable!(),
                 }
             }
             "stories" => {
                 s.remove(0);
-                let offset = s.find(|c: char| c == '/').ok_or_else(|| {
+                let offset = s.find('/').ok_or_else(|| {
                     MediaLinkParseError::Invalid("owner_username not found".to_owned())
                 })?;
                 let owner_username: String = s.drain(..offset).collect();
 
                 s.remove(0);
-                let ig_id = if let Some(offset) = s.find(|c: char| c == '/') {
+                let ig_id = if let Some(offset) = s.find('/') {
                     s.drain(..offset).collect::<String>()
                 } else {
                     s.to_owned()
@@ -92,7 +141,7 @@ impl MediaLink {
             }
             "s" => {
                 s.remove(0);
-                let highlight_b64_encoded = if let Some(offset) = s.find(|c: char| c == '/') {
+                let highlight_b64_encoded = if let Some(offset) = s.find('/') {
                     s.drain(..offset).collect::<String>()
                 } else {
                     s.to_owned()
@@ -162,6 +211,45 @@ impl MediaLink {
             Self::Reel { metadata } => metadata,
         }
     }
+
+    /// The canonical form, which [`MediaLink::parse`] turns back into `self`.
+    pub fn to_url(&self) -> String {
+        match self {
+            Self::Post { metadata } => {
+                format!("https://www.instagram.com/p/{}/", metadata.shortcode)
+            }
+            Self::Story {
+                metadata,
+                owner_username,
+            } => format!(
+                "https://www.instagram.com/stories/{}/{}/",
+                owner_username, metadata.ig_id
+            ),
+            Self::StoryHighlight {
+                metadata,
+                highlight_id,
+            } => format!(
+                "https://www.instagram.com/s/{}?story_media_id={}",
+                base64::encode(format!(
+                    "highlight:{}",
+                    highlight_id.map(|x| x.to_string()).unwrap_or_default()
+                )),
+                metadata.ig_id
+            ),
+            Self::IGTVVideo { metadata } => {
+                format!("https://www.instagram.com/tv/{}/", metadata.shortcode)
+            }
+            Self::Reel { metadata } => {
+                format!("https://www.instagram.com/reel/{}/", metadata.shortcode)
+            }
+        }
+    }
+}
+
+impl fmt::Display for MediaLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_url())
+    }
 }
 
 #[cfg(test)]
@@ -212,6 +300,180 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_with_owner_username() -> Result<(), Box<dyn error::Error>> {
+        assert_eq!(
+            MediaLink::parse_with_owner_username(
+                "https://www.instagram.com/foo.bar/p/CJBsZ11MYha/"
+            )?,
+            (
+                MediaLink::Post {
+                    metadata: MediaMetadata::with_shortcode("CJBsZ11MYha".to_owned())?
+                },
+                Some("foo.bar".to_owned())
+            )
+        );
+
+        for url in [
+            "https://www.instagram.com/foo/reel/CH-__hxDV7T/?igshid=ffffffffffff",
+            "https://www.instagram.com/foo/reels/CH-__hxDV7T",
+        ] {
+            let parsed = MediaLink::parse_with_policy(url, &HostPolicy::Strict)?;
+            assert_eq!(
+                parsed.link,
+                MediaLink::Reel {
+                    metadata: MediaMetadata::with_shortcode("CH-__hxDV7T".to_owned())?
+                }
+            );
+            assert_eq!(parsed.owner_username.as_deref(), Some("foo"));
+        }
+
+        assert_eq!(
+            MediaLink::parse_with_owner_username("https://www.instagram.com/p/CJBsZ11MYha/")?.1,
+            None
+        );
+
+        for url in [
+            "https://www.instagram.com/foo/",
+            "https://www.instagram.com/foo/tv/CJEivokDjPR/",
+            "https://www.instagram.com/foo/tagged/",
+            "https://www.instagram.com/foo/reels/",
+            "https://www.instagram.com/foo/reels",
+            "https://www.instagram.com/foo/reel/",
+            "https://www.instagram.com/foo/p/",
+            "https://www.instagram.com/explore/p/CJBsZ11MYha/",
+        ] {
+            assert_eq!(
+                MediaLink::parse(url),
+                Err(MediaLinkParseError::Unsupported),
+                "url: {}",
+                url
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_with_policy() -> Result<(), Box<dyn error::Error>> {
+        let post = MediaLink::Post {
+            metadata: MediaMetadata::with_shortcode("CJBsZ11MYha".to_owned())?,
+        };
+
+        for (url, scheme, host) in [
+            ("https://instagr.am/p/CJBsZ11MYha/", "https", "instagr.am"),
+            (
+                "http://m.instagram.com/p/CJBsZ11MYha",
+                "http",
+                "m.instagram.com",
+            ),
+            (
+                "https://WWW.INSTAGRAM.COM./p/CJBsZ11MYha/",
+                "https",
+                "www.instagram.com.",
+            ),
+            ("https://ig.me/p/CJBsZ11MYha/", "https", "ig.me"),
+        ] {
+            assert_eq!(
+                MediaLink::parse(url).err(),
+                Some(MediaLinkParseError::Invalid(
+                    if scheme == "http" {
+                        "scheme mismatch"
+                    } else {
+                        "host mismatch"
+                    }
+                    .to_owned()
+                )),
+                "url: {}",
+                url
+            );
+
+            let parsed = MediaLink::parse_with_policy(url, &HostPolicy::Lenient)?;
+            assert_eq!(
+                parsed,
+                ParsedLink {
+                    link: post.to_owned(),
+                    scheme: scheme.to_owned(),
+                    host: host.to_owned(),
+                    redirects: vec![],
+                    share: None,
+                    owner_username: None,
+                }
+            );
+            assert_eq!(parsed.is_upgraded(), scheme == "http");
+            assert_eq!(
+                parsed.link.to_url(),
+                "https://www.instagram.com/p/CJBsZ11MYha/"
+            );
+        }
+
+        let policy = HostPolicy::Custom(vec!["instagram.example.com".to_owned()]);
+        assert_eq!(
+            MediaLink::parse_with_policy("http://instagram.example.com/p/CJBsZ11MYha/", &policy)?
+                .link,
+            post
+        );
+        assert_eq!(
+            MediaLink::parse_with_policy("https://instagram.com/p/CJBsZ11MYha/", &policy),
+            Err(MediaLinkParseError::Invalid("host mismatch".to_owned()))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_redirect() -> Result<(), Box<dyn error::Error>> {
+        let url =
+            "https://l.instagram.com/?u=https%3A%2F%2Fwww.instagram.com%2Fp%2FCJBsZ11MYha%2F&e=ATM";
+        assert_eq!(
+            MediaLink::parse(url)?,
+            MediaLink::Post {
+                metadata: MediaMetadata::with_shortcode("CJBsZ11MYha".to_owned())?
+            }
+        );
+        assert_eq!(
+            MediaLink::parse_with_policy(url, &HostPolicy::Strict)?.redirects,
+            vec![url.to_owned()]
+        );
+
+        assert_eq!(
+            MediaLink::parse(
+                "https://l.instagram.com/?u=https%3A%2F%2Fexample.com%2Fp%2FCJBsZ11MYha%2F"
+            ),
+            Err(MediaLinkParseError::Invalid("host mismatch".to_owned()))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_share_context() -> Result<(), Box<dyn error::Error>> {
+        let parsed = MediaLink::parse_with_policy(
+            "https://www.instagram.com/p/CJBsZ11MYha/?img_index=2&igshid=ffffffffffff",
+            &HostPolicy::Strict,
+        )?;
+        assert_eq!(
+            parsed.share,
+            Some(ShareContext {
+                img_index: Some(2),
+                share_id: Some("ffffffffffff".to_owned()),
+                share_id_param: Some(ShareIdParam::Igshid),
+                ..Default::default()
+            })
+        );
+
+        assert_eq!(
+            MediaLink::parse_with_policy(
+                "https://www.instagram.com/p/CJBsZ11MYha/",
+                &HostPolicy::Strict
+            )?
+            .share,
+            None
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_parse_tv() -> Result<(), Box<dyn error::Error>> {
         let link =
@@ -245,6 +507,33 @@ mod tests {
             }
         );
 
+        let link = MediaLink::parse("https://www.instagram.com/reels/CH-__hxDV7T/")?;
+        assert_eq!(
+            link,
+            MediaLink::Reel {
+                metadata: MediaMetadata {
+                    ig_id: 2449676689849802451,
+                    shortcode: "CH-__hxDV7T".to_owned(),
+                    is_public_shortcode: Some(true)
+                }
+            }
+        );
+
+        for url in [
+            "https://www.instagram.com/reels/audio/1/",
+            "https://www.instagram.com/reels/",
+            "https://www.instagram.com/reel/",
+            "https://www.instagram.com/p/",
+            "https://www.instagram.com/tv/",
+        ] {
+            assert_eq!(
+                MediaLink::parse(url),
+                Err(MediaLinkParseError::Unsupported),
+                "url: {}",
+                url
+            );
+        }
+
         Ok(())
     }
 
@@ -313,4 +602,51 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_to_url() -> Result<(), Box<dyn error::Error>> {
+        for (url, canonical) in [
+            (
+                "https://www.instagram.com/p/CJBsZ11MYha?igshid=ffffffffffff",
+                "https://www.instagram.com/p/CJBsZ11MYha/",
+            ),
+            (
+                "https://instagram.com/foo/reels/CH-__hxDV7T",
+                "https://www.instagram.com/reel/CH-__hxDV7T/",
+            ),
+            (
+                "https://www.instagram.com/p/CH5LLEGnhWDZpMs--h6rwCecLT3So9_ZOwTKCk0/",
+                "https://www.instagram.com/p/CH5LLEGnhWDZpMs--h6rwCecLT3So9_ZOwTKCk0/",
+            ),
+            (
+                "https://instagram.com/tv/CJEivokDjPR",
+                "https://www.instagram.com/tv/CJEivokDjPR/",
+            ),
+            (
+                "https://www.instagram.com/reel/CH-__hxDV7T/?igshid=ffffffffffff",
+                "https://www.instagram.com/reel/CH-__hxDV7T/",
+            ),
+            (
+                "https://instagram.com/stories/foo/1?utm_source=ig_story_item_share",
+                "https://www.instagram.com/stories/foo/1/",
+            ),
+            (
+                "https://www.instagram.com/s/aGlnaGxpZ2h0OjE4MDY2MTI4ODAzMTg4MjY3/?igshid=ffffffffffff&story_media_id=1",
+                "https://www.instagram.com/s/aGlnaGxpZ2h0OjE4MDY2MTI4ODAzMTg4MjY3?story_media_id=1",
+            ),
+        ] {
+            let link = MediaLink::parse(url)?;
+            assert_eq!(link.to_url(), canonical);
+            assert_eq!(link.to_string(), canonical);
+            assert_eq!(MediaLink::parse(link.to_url())?, link);
+        }
+
+        let link = MediaLink::StoryHighlight {
+            metadata: MediaMetadata::with_ig_id(1),
+            highlight_id: None,
+        };
+        assert_eq!(MediaLink::parse(link.to_url())?, link);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,444 @@
+use core::fmt;
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use url::Url;
+
+use crate::{unwrap_redirects, HostPolicy, MediaLink, MediaLinkParseError, ParsedLink, Unwrapped};
+
+pub const USERNAME_MAX_LEN: usize = 30;
+
+// RFC 3986 unreserved chars are kept
+const TAG_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// First path segments that are not usernames.
+pub const RESERVED_PATHS: &[&str] = &[
+    "about",
+    "accounts",
+    "api",
+    "ar",
+    "challenge",
+    "developer",
+    "direct",
+    "emails",
+    "explore",
+    "graphql",
+    "legal",
+    "lite",
+    "nametag",
+    "p",
+    "press",
+    "privacy",
+    "reel",
+    "reels",
+    "s",
+    "session",
+    "static",
+    "stories",
+    "terms",
+    "tv",
+    "web",
+];
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstagramLink {
+    Media(MediaLink),
+    Profile {
+        username: String,
+    },
+    ProfileTab {
+        username: String,
+        tab: ProfileTab,
+    },
+    Live {
+        username: String,
+    },
+    /// The tag without the leading `#`, percent-decoded.
+    Tag {
+        tag: String,
+    },
+    Location {
+        id: u64,
+        slug: Option<String>,
+    },
+    /// Reels original audio.
+    Audio {
+        id: u64,
+    },
+    /// AR effect.
+    Effect {
+        id: u64,
+    },
+}
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProfileTab {
+    Reels,
+    Tagged,
+    Guides,
+}
+
+impl ProfileTab {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Reels => "reels",
+            Self::Tagged => "tagged",
+            Self::Guides => "guides",
+        }
+    }
+}
+
+impl InstagramLink {
+    pub fn parse(url: impl AsRef<str>) -> Result<Self, MediaLinkParseError> {
+        Self::parse_with_policy(url, &HostPolicy::Strict).map(|x| x.link)
+    }
+
+    pub fn parse_with_policy(
+        url: impl AsRef<str>,
+        policy: &HostPolicy,
+    ) -> Result<ParsedLink<Self>, MediaLinkParseError> {
+        let Unwrapped { url, redirects } = unwrap_redirects(url)?;
+
+        policy.check(&url)?;
+
+        Self::parse_url(&url).map(|x| ParsedLink::new(x, &url, redirects))
+    }
+
+    pub(crate) fn parse_url(parsed: &Url) -> Result<Self, MediaLinkParseError> {
+        let segments = parsed
+            .path_segments()
+            .map(|x| x.filter(|x| !x.is_empty()).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        match segments.as_slice() {
+            [] => Err(MediaLinkParseError::Unsupported),
+            ["reels", "audio", id] => Ok(Self::Audio {
+                id: id
+                    .parse()
+                    .map_err(|_| MediaLinkParseError::Invalid("audio id invalid".to_owned()))?,
+            }),
+            ["ar", id] => Ok(Self::Effect {
+                id: id
+                    .parse()
+                    .map_err(|_| MediaLinkParseError::Invalid("effect id invalid".to_owned()))?,
+            }),
+            [first, ..] if matches!(*first, "p" | "tv" | "reel" | "reels" | "stories" | "s") => {
+                MediaLink::parse_url(parsed).map(Self::Media)
+            }
+            ["explore", "tags", tag] => {
+                let tag = percent_decode_str(tag)
+                    .decode_utf8()
+                    .map_err(|_| MediaLinkParseError::Invalid("tag invalid".to_owned()))?;
+                let tag = tag.strip_prefix('#').unwrap_or(&tag);
+                if tag.is_empty() {
+                    return Err(MediaLinkParseError::Invalid("tag not found".to_owned()));
+                }
+                Ok(Self::Tag {
+                    tag: tag.to_owned(),
+                })
+            }
+            ["explore", "locations", id, slug @ ..] if slug.len() <= 1 => Ok(Self::Location {
+                id: id
+                    .parse()
+                    .map_err(|_| MediaLinkParseError::Invalid("location id invalid".to_owned()))?,
+                slug: slug.first().map(|x| (*x).to_owned()),
+            }),
+            [first, ..] if RESERVED_PATHS.contains(first) => Err(MediaLinkParseError::Unsupported),
+            [_, "p" | "reel" | "reels", _, ..] => MediaLink::parse_url(parsed).map(Self::Media),
+            [username, rest @ ..] => {
+                if !is_valid_username(username) {
+                    return Err(MediaLinkParseError::Invalid("username invalid".to_owned()));
+                }
+                let username = (*username).to_owned();
+
+                match rest {
+                    [] => Ok(Self::Profile { username }),
+                    ["reels"] => Ok(Self::ProfileTab {
+                        username,
+                        tab: ProfileTab::Reels,
+                    }),
+                    ["tagged"] => Ok(Self::ProfileTab {
+                        username,
+                        tab: ProfileTab::Tagged,
+                    }),
+                    ["guides"] => Ok(Self::ProfileTab {
+                        username,
+                        tab: ProfileTab::Guides,
+                    }),
+                    ["live"] => Ok(Self::Live { username }),
+                    _ => Err(MediaLinkParseError::Unsupported),
+                }
+            }
+        }
+    }
+
+    pub fn to_url(&self) -> String {
+        match self {
+            Self::Media(link) => link.to_url(),
+            Self::Profile { username } => format!("https://www.instagram.com/{}/", username),
+            Self::ProfileTab { username, tab } => {
+                format!("https://www.instagram.com/{}/{}/", username, tab.as_str())
+            }
+            Self::Live { username } => format!("https://www.instagram.com/{}/live/", username),
+            Self::Tag { tag } => format!(
+                "https://www.instagram.com/explore/tags/{}/",
+                utf8_percent_encode(tag, TAG_ENCODE_SET)
+            ),
+            Self::Location {
+                id,
+                slug: Some(slug),
+            } => {
+                format!(
+                    "https://www.instagram.com/explore/locations/{}/{}/",
+                    id, slug
+                )
+            }
+            Self::Location { id, slug: None } => {
+                format!("https://www.instagram.com/explore/locations/{}/", id)
+            }
+            Self::Audio { id } => format!("https://www.instagram.com/reels/audio/{}/", id),
+            Self::Effect { id } => format!("https://www.instagram.com/ar/{}/", id),
+        }
+    }
+}
+
+impl fmt::Display for InstagramLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_url())
+    }
+}
+
+/// 1 to 30 ASCII letters, digits, `_` or `.`, not starting or ending with a `.` and without `..`.
+pub fn is_valid_username(s: &str) -> bool {
+    !s.is_empty()
+        && s.len() <= USERNAME_MAX_LEN
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'.')
+        && !s.starts_with('.')
+        && !s.ends_with('.')
+        && !s.contains("..")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    use crate::MediaMetadata;
+
+    #[test]
+    fn test_parse() -> Result<(), Box<dyn error::Error>> {
+        assert_eq!(
+            InstagramLink::parse("https://www.instagram.com/foo.bar_1/?hl=en")?,
+            InstagramLink::Profile {
+                username: "foo.bar_1".to_owned()
+            }
+        );
+        assert_eq!(
+            InstagramLink::parse("https://instagram.com/foo")?,
+            InstagramLink::Profile {
+                username: "foo".to_owned()
+            }
+        );
+        assert_eq!(
+            InstagramLink::parse("https://www.instagram.com/foo/reels/")?,
+            InstagramLink::ProfileTab {
+                username: "foo".to_owned(),
+                tab: ProfileTab::Reels
+            }
+        );
+        assert_eq!(
+            InstagramLink::parse("https://www.instagram.com/foo/tagged")?,
+            InstagramLink::ProfileTab {
+                username: "foo".to_owned(),
+                tab: ProfileTab::Tagged
+            }
+        );
+        assert_eq!(
+            InstagramLink::parse("https://www.instagram.com/foo/guides/")?,
+            InstagramLink::ProfileTab {
+                username: "foo".to_owned(),
+                tab: ProfileTab::Guides
+            }
+        );
+        assert_eq!(
+            InstagramLink::parse("https://www.instagram.com/foo/live/")?,
+            InstagramLink::Live {
+                username: "foo".to_owned()
+            }
+        );
+        assert_eq!(
+            InstagramLink::parse("https://www.instagram.com/explore/tags/caf%C3%A9/")?,
+            InstagramLink::Tag {
+                tag: "café".to_owned()
+            }
+        );
+        assert_eq!(
+            InstagramLink::parse(
+                "https://www.instagram.com/explore/locations/212988663/new-york-new-york/"
+            )?,
+            InstagramLink::Location {
+                id: 212988663,
+                slug: Some("new-york-new-york".to_owned())
+            }
+        );
+        assert_eq!(
+            InstagramLink::parse("https://www.instagram.com/explore/locations/212988663")?,
+            InstagramLink::Location {
+                id: 212988663,
+                slug: None
+            }
+        );
+        assert_eq!(
+            InstagramLink::parse("https://www.instagram.com/reels/audio/488856168978373/")?,
+            InstagramLink::Audio {
+                id: 488856168978373
+            }
+        );
+        assert_eq!(
+            InstagramLink::parse("https://www.instagram.com/ar/623487665179413")?,
+            InstagramLink::Effect {
+                id: 623487665179413
+            }
+        );
+        assert_eq!(
+            InstagramLink::parse("https://www.instagram.com/reels/CH-__hxDV7T/")?,
+            InstagramLink::Media(MediaLink::Reel {
+                metadata: MediaMetadata::with_shortcode("CH-__hxDV7T".to_owned())?
+            })
+        );
+        assert_eq!(
+            InstagramLink::parse("https://www.instagram.com/p/CJBsZ11MYha/")?,
+            InstagramLink::Media(MediaLink::Post {
+                metadata: MediaMetadata::with_shortcode("CJBsZ11MYha".to_owned())?
+            })
+        );
+        let parsed = InstagramLink::parse_with_policy(
+            "https://www.instagram.com/foo/p/CJBsZ11MYha/",
+            &HostPolicy::Strict,
+        )?;
+        assert_eq!(
+            parsed.link,
+            InstagramLink::Media(MediaLink::Post {
+                metadata: MediaMetadata::with_shortcode("CJBsZ11MYha".to_owned())?
+            })
+        );
+        assert_eq!(parsed.owner_username.as_deref(), Some("foo"));
+        assert_eq!(
+            InstagramLink::parse("https://www.instagram.com/foo/p/CJBsZ11MYha/extra")?,
+            InstagramLink::Media(MediaLink::Post {
+                metadata: MediaMetadata::with_shortcode("CJBsZ11MYha".to_owned())?
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_with_policy() -> Result<(), Box<dyn error::Error>> {
+        let parsed =
+            InstagramLink::parse_with_policy("http://m.instagram.com/foo/", &HostPolicy::Lenient)?;
+        assert_eq!(
+            parsed.link,
+            InstagramLink::Profile {
+                username: "foo".to_owned()
+            }
+        );
+        assert_eq!(parsed.host, "m.instagram.com");
+        assert!(parsed.is_upgraded());
+
+        assert_eq!(
+            InstagramLink::parse_with_policy(
+                "https://instagr.am/p/CJBsZ11MYha/",
+                &HostPolicy::Lenient
+            )?
+            .link
+            .to_url(),
+            "https://www.instagram.com/p/CJBsZ11MYha/"
+        );
+
+        assert_eq!(
+            InstagramLink::parse("http://www.instagram.com/foo/"),
+            Err(MediaLinkParseError::Invalid("scheme mismatch".to_owned()))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() {
+        for url in [
+            "https://www.instagram.com/",
+            "https://www.instagram.com/explore/",
+            "https://www.instagram.com/accounts/login/",
+            "https://www.instagram.com/direct/inbox/",
+            "https://www.instagram.com/foo/bar/",
+            "https://www.instagram.com/explore/tags/",
+            "https://www.instagram.com/explore/locations/1/foo/bar/",
+            "https://www.instagram.com/ar/",
+            "https://www.instagram.com/reels/",
+        ] {
+            assert_eq!(
+                InstagramLink::parse(url),
+                Err(MediaLinkParseError::Unsupported),
+                "url: {}",
+                url
+            );
+        }
+
+        for url in [
+            "https://www.instagram.com/.foo/",
+            "https://www.instagram.com/foo..bar/",
+            "https://www.instagram.com/foo-bar/",
+            "https://www.instagram.com/aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa/",
+        ] {
+            assert_eq!(
+                InstagramLink::parse(url),
+                Err(MediaLinkParseError::Invalid("username invalid".to_owned())),
+                "url: {}",
+                url
+            );
+        }
+
+        assert_eq!(
+            InstagramLink::parse("https://www.instagram.com/reels/audio/foo/"),
+            Err(MediaLinkParseError::Invalid("audio id invalid".to_owned()))
+        );
+        assert_eq!(
+            InstagramLink::parse("https://www.instagram.com/explore/locations/foo/"),
+            Err(MediaLinkParseError::Invalid(
+                "location id invalid".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_to_url() -> Result<(), Box<dyn error::Error>> {
+        for url in [
+            "https://www.instagram.com/foo/",
+            "https://www.instagram.com/foo/reels/",
+            "https://www.instagram.com/foo/tagged/",
+            "https://www.instagram.com/foo/guides/",
+            "https://www.instagram.com/foo/live/",
+            "https://www.instagram.com/explore/tags/summer_vibes/",
+            "https://www.instagram.com/explore/tags/%E6%9D%B1%E4%BA%AC/",
+            "https://www.instagram.com/explore/locations/212988663/new-york-new-york/",
+            "https://www.instagram.com/explore/locations/212988663/",
+            "https://www.instagram.com/reels/audio/488856168978373/",
+            "https://www.instagram.com/ar/623487665179413/",
+            "https://www.instagram.com/p/CJBsZ11MYha/",
+        ] {
+            let link = InstagramLink::parse(url)?;
+            assert_eq!(link.to_url(), url);
+            assert_eq!(link.to_string(), url);
+        }
+
+        Ok(())
+    }
+}
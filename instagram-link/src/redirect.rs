@@ -0,0 +1,106 @@
+use url::Url;
+
+use crate::MediaLinkParseError;
+
+/// Wrappers nested deeper than this are not unwrapped further.
+pub const MAX_REDIRECTS: usize = 10;
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unwrapped {
+    pub url: Url,
+    /// The wrapper URLs, outermost first, empty when `url` wasn't wrapped.
+    pub redirects: Vec<String>,
+}
+
+/// Decodes `https://l.instagram.com/?u=<url>` and `https://l.facebook.com/l.php?u=<url>` wrappers,
+/// which may be nested. Nothing is fetched.
+pub fn unwrap_redirects(url: impl AsRef<str>) -> Result<Unwrapped, MediaLinkParseError> {
+    let mut url = Url::parse(url.as_ref()).map_err(MediaLinkParseError::UrlParseError)?;
+
+    let mut redirects = vec![];
+    while redirects.len() < MAX_REDIRECTS && is_wrapper(&url) {
+        let (_, target) = url
+            .query_pairs()
+            .find(|(k, _)| k == "u")
+            .ok_or_else(|| MediaLinkParseError::Invalid("redirect target not found".to_owned()))?;
+        let target = Url::parse(&target).map_err(MediaLinkParseError::UrlParseError)?;
+
+        redirects.push(url.to_string());
+        url = target;
+    }
+
+    Ok(Unwrapped { url, redirects })
+}
+
+pub(crate) fn is_wrapper(url: &Url) -> bool {
+    if !matches!(url.scheme(), "https" | "http") {
+        return false;
+    }
+    match url.host_str().map(|x| x.trim_end_matches('.')) {
+        Some("l.instagram.com") => true,
+        Some("l.facebook.com" | "lm.facebook.com" | "m.facebook.com" | "www.facebook.com") => {
+            url.path() == "/l.php"
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_unwrap_redirects() -> Result<(), Box<dyn error::Error>> {
+        let unwrapped = unwrap_redirects(
+            "https://l.instagram.com/?u=https%3A%2F%2Fwww.instagram.com%2Fp%2FCJBsZ11MYha%2F%3Figshid%3Dffffffffffff&e=ATM",
+        )?;
+        assert_eq!(
+            unwrapped.url.as_str(),
+            "https://www.instagram.com/p/CJBsZ11MYha/?igshid=ffffffffffff"
+        );
+        assert_eq!(
+            unwrapped.redirects,
+            vec!["https://l.instagram.com/?u=https%3A%2F%2Fwww.instagram.com%2Fp%2FCJBsZ11MYha%2F%3Figshid%3Dffffffffffff&e=ATM"]
+        );
+
+        let unwrapped = unwrap_redirects(
+            "https://l.facebook.com/l.php?u=https%3A%2F%2Fl.instagram.com%2F%3Fu%3Dhttps%253A%252F%252Finstagram.com%252Ffoo%252F%26e%3DATM&h=AT0",
+        )?;
+        assert_eq!(unwrapped.url.as_str(), "https://instagram.com/foo/");
+        assert_eq!(unwrapped.redirects.len(), 2);
+        assert!(unwrapped.redirects[0].starts_with("https://l.facebook.com/"));
+        assert!(unwrapped.redirects[1].starts_with("https://l.instagram.com/"));
+
+        let unwrapped = unwrap_redirects("https://www.instagram.com/p/CJBsZ11MYha/")?;
+        assert!(unwrapped.redirects.is_empty());
+        let unwrapped = unwrap_redirects("https://www.facebook.com/foo?u=https://example.com/")?;
+        assert!(unwrapped.redirects.is_empty());
+
+        assert_eq!(
+            unwrap_redirects("https://l.instagram.com/?e=ATM"),
+            Err(MediaLinkParseError::Invalid(
+                "redirect target not found".to_owned()
+            ))
+        );
+        assert!(matches!(
+            unwrap_redirects("https://l.instagram.com/?u=foo"),
+            Err(MediaLinkParseError::UrlParseError(_))
+        ));
+
+        let mut url = "https://www.instagram.com/foo/".to_owned();
+        for _ in 0..MAX_REDIRECTS + 1 {
+            url = format!(
+                "https://l.instagram.com/?u={}",
+                percent_encoding::utf8_percent_encode(&url, percent_encoding::NON_ALPHANUMERIC)
+            );
+        }
+        let unwrapped = unwrap_redirects(&url)?;
+        assert_eq!(unwrapped.redirects.len(), MAX_REDIRECTS);
+        assert_eq!(unwrapped.url.host_str(), Some("l.instagram.com"));
+
+        Ok(())
+    }
+}
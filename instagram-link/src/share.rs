@@ -0,0 +1,358 @@
+use url::{form_urlencoded, Url};
+
+use crate::{redirect::is_wrapper, MediaLinkParseError, MAX_REDIRECTS};
+
+/// Query params holding a share id.
+pub const SHARE_ID_PARAMS: &[&str] = &["igsh", "igshid"];
+
+//
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShareContext {
+    /// `img_index`, the carousel slide, 1-based.
+    pub img_index: Option<u32>,
+    /// `igsh`, or the older `igshid`.
+    pub share_id: Option<String>,
+    /// The param `share_id` came from.
+    pub share_id_param: Option<ShareIdParam>,
+    pub utm_source: Option<String>,
+    /// `hl`, the language, e.g. `en`.
+    pub hl: Option<String>,
+}
+
+impl ShareContext {
+    /// `None` when the query has none of the params.
+    pub fn from_url(url: &Url) -> Option<Self> {
+        let mut ctx = Self::default();
+        let mut igshid = None;
+        for (k, v) in url.query_pairs() {
+            if v.is_empty() {
+                continue;
+            }
+            match k.as_ref() {
+                "img_index" => ctx.img_index = v.parse().ok().filter(|x| *x > 0),
+                "igsh" => {
+                    ctx.share_id = Some(v.into_owned());
+                    ctx.share_id_param = Some(ShareIdParam::Igsh);
+                }
+                "igshid" => igshid = Some(v.into_owned()),
+                "utm_source" => ctx.utm_source = Some(v.into_owned()),
+                "hl" => ctx.hl = Some(v.into_owned()),
+                _ => {}
+            }
+        }
+        if ctx.share_id.is_none() && igshid.is_some() {
+            ctx.share_id = igshid;
+            ctx.share_id_param = Some(ShareIdParam::Igshid);
+        }
+
+        if ctx == Self::default() {
+            None
+        } else {
+            Some(ctx)
+        }
+    }
+
+    /// `None` without `share_id_param`, the format depends on it.
+    pub fn decode_share_id(&self) -> Option<ShareId> {
+        self.share_id
+            .as_deref()
+            .zip(self.share_id_param)
+            .map(|(s, param)| ShareId::decode(s, param))
+    }
+}
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShareIdParam {
+    Igsh,
+    Igshid,
+}
+
+impl ShareIdParam {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Igsh => "igsh",
+            Self::Igshid => "igshid",
+        }
+    }
+}
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShareIdFormat {
+    /// `igsh`, base64 of a hex string.
+    Base64Hex,
+    /// `igsh`, base64 of some other text.
+    Base64Text,
+    /// `igshid`.
+    Hex,
+    /// `igshid`, letters and digits without a known structure.
+    Alphanumeric,
+    Unknown,
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareId {
+    pub raw: String,
+    pub param: ShareIdParam,
+    pub format: ShareIdFormat,
+    /// The decoded text for `Base64Hex` and `Base64Text`.
+    pub payload: Option<String>,
+}
+
+impl ShareId {
+    /// `igsh` is base64, `igshid` is hex or alphanumeric.
+    pub fn decode(s: &str, param: ShareIdParam) -> Self {
+        let (format, payload) = match param {
+            ShareIdParam::Igsh => match decode_base64_text(s) {
+                Some(text) if text.bytes().all(|b| b.is_ascii_hexdigit()) => {
+                    (ShareIdFormat::Base64Hex, Some(text))
+                }
+                Some(text) => (ShareIdFormat::Base64Text, Some(text)),
+                None => (ShareIdFormat::Unknown, None),
+            },
+            ShareIdParam::Igshid if s.is_empty() => (ShareIdFormat::Unknown, None),
+            ShareIdParam::Igshid if s.bytes().all(|b| b.is_ascii_hexdigit()) => {
+                (ShareIdFormat::Hex, None)
+            }
+            ShareIdParam::Igshid if s.bytes().all(|b| b.is_ascii_alphanumeric()) => {
+                (ShareIdFormat::Alphanumeric, None)
+            }
+            ShareIdParam::Igshid => (ShareIdFormat::Unknown, None),
+        };
+
+        Self {
+            raw: s.to_owned(),
+            param,
+            format,
+            payload,
+        }
+    }
+}
+
+/// Removes the [`SHARE_ID_PARAMS`], which tie a link to the account that shared it.
+///
+/// The other params are kept as written. The target of `l.instagram.com` and `l.facebook.com` wrappers,
+/// see [`unwrap_redirects`](crate::unwrap_redirects), is stripped too.
+pub fn strip_share_ids(url: impl AsRef<str>) -> Result<String, MediaLinkParseError> {
+    let mut url = Url::parse(url.as_ref()).map_err(MediaLinkParseError::UrlParseError)?;
+    strip_url(&mut url, 0);
+    Ok(url.to_string())
+}
+
+fn strip_url(url: &mut Url, depth: usize) {
+    let query = match url.query() {
+        Some(query) => query,
+        None => return,
+    };
+    let is_wrapper = depth < MAX_REDIRECTS && is_wrapper(url);
+
+    let mut changed = false;
+    let pairs = query
+        .split('&')
+        .filter_map(|pair| {
+            let (k, v) = form_urlencoded::parse(pair.as_bytes())
+                .next()
+                .unwrap_or_default();
+            if SHARE_ID_PARAMS.contains(&k.as_ref()) {
+                changed = true;
+                return None;
+            }
+            if is_wrapper && k == "u" {
+                if let Ok(mut target) = Url::parse(&v) {
+                    strip_url(&mut target, depth + 1);
+                    if target.as_str() != v {
+                        changed = true;
+                        return Some(format!(
+                            "u={}",
+                            form_urlencoded::byte_serialize(target.as_str().as_bytes())
+                                .collect::<String>()
+                        ));
+                    }
+                }
+            }
+            Some(pair.to_owned())
+        })
+        .collect::<Vec<_>>();
+
+    if changed {
+        if pairs.is_empty() {
+            url.set_query(None);
+        } else {
+            url.set_query(Some(&pairs.join("&")));
+        }
+    }
+}
+
+/// Printable ASCII text only, so random ids that happen to be valid base64 are not decoded.
+fn decode_base64_text(s: &str) -> Option<String> {
+    let bytes = base64::decode(s)
+        .or_else(|_| base64::decode_config(s, base64::URL_SAFE_NO_PAD))
+        .ok()?;
+    if bytes.is_empty() || !bytes.iter().all(|b| b.is_ascii_graphic()) {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error;
+
+    #[test]
+    fn test_from_url() -> Result<(), Box<dyn error::Error>> {
+        assert_eq!(
+            ShareContext::from_url(&Url::parse(
+                "https://www.instagram.com/p/CJBsZ11MYha/?img_index=3&igsh=MTc4MmM1YmI2Ng%3D%3D&utm_source=ig_web_copy_link&hl=en"
+            )?),
+            Some(ShareContext {
+                img_index: Some(3),
+                share_id: Some("MTc4MmM1YmI2Ng==".to_owned()),
+                share_id_param: Some(ShareIdParam::Igsh),
+                utm_source: Some("ig_web_copy_link".to_owned()),
+                hl: Some("en".to_owned()),
+            })
+        );
+        assert_eq!(
+            ShareContext::from_url(&Url::parse(
+                "https://www.instagram.com/p/CJBsZ11MYha/?igshid=ffffffffffff&img_index=0"
+            )?),
+            Some(ShareContext {
+                share_id: Some("ffffffffffff".to_owned()),
+                share_id_param: Some(ShareIdParam::Igshid),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            ShareContext::from_url(&Url::parse(
+                "https://www.instagram.com/p/CJBsZ11MYha/?img_index=x&foo=bar&hl="
+            )?),
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_share_id() {
+        assert_eq!(
+            ShareId::decode("MTc4MmM1YmI2Ng==", ShareIdParam::Igsh),
+            ShareId {
+                raw: "MTc4MmM1YmI2Ng==".to_owned(),
+                param: ShareIdParam::Igsh,
+                format: ShareIdFormat::Base64Hex,
+                payload: Some("1782c5bb66".to_owned()),
+            }
+        );
+        assert_eq!(
+            ShareId::decode("MTc4MmM1YmI2Zg", ShareIdParam::Igsh).payload,
+            Some("1782c5bb6f".to_owned())
+        );
+        assert_eq!(
+            ShareId::decode("aGVsbG8=", ShareIdParam::Igsh).format,
+            ShareIdFormat::Base64Text
+        );
+        // hex digits only, but valid base64
+        assert_eq!(
+            ShareId::decode("d211b200", ShareIdParam::Igsh),
+            ShareId {
+                raw: "d211b200".to_owned(),
+                param: ShareIdParam::Igsh,
+                format: ShareIdFormat::Base64Text,
+                payload: Some("wmuom4".to_owned()),
+            }
+        );
+        assert_eq!(
+            ShareId::decode("ffffffffffff", ShareIdParam::Igsh).format,
+            ShareIdFormat::Unknown
+        );
+        assert_eq!(
+            ShareId::decode("", ShareIdParam::Igsh).format,
+            ShareIdFormat::Unknown
+        );
+
+        assert_eq!(
+            ShareId::decode("d211b200", ShareIdParam::Igshid),
+            ShareId {
+                raw: "d211b200".to_owned(),
+                param: ShareIdParam::Igshid,
+                format: ShareIdFormat::Hex,
+                payload: None,
+            }
+        );
+        assert_eq!(
+            ShareId::decode("1wqyj0oxa6l2z", ShareIdParam::Igshid).format,
+            ShareIdFormat::Alphanumeric
+        );
+        assert_eq!(
+            ShareId::decode("MTc4MmM1YmI2Ng==", ShareIdParam::Igshid).format,
+            ShareIdFormat::Unknown
+        );
+        assert_eq!(
+            ShareId::decode("a b", ShareIdParam::Igshid).format,
+            ShareIdFormat::Unknown
+        );
+        assert_eq!(
+            ShareId::decode("", ShareIdParam::Igshid).format,
+            ShareIdFormat::Unknown
+        );
+
+        assert_eq!(
+            ShareContext {
+                share_id: Some("ffffffffffff".to_owned()),
+                share_id_param: Some(ShareIdParam::Igshid),
+                ..Default::default()
+            }
+            .decode_share_id()
+            .map(|x| x.format),
+            Some(ShareIdFormat::Hex)
+        );
+        assert_eq!(
+            ShareContext {
+                share_id: Some("ffffffffffff".to_owned()),
+                ..Default::default()
+            }
+            .decode_share_id(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_strip_share_ids() -> Result<(), Box<dyn error::Error>> {
+        assert_eq!(
+            strip_share_ids("https://www.instagram.com/p/CJBsZ11MYha/?igsh=MTc4MmM1YmI2Ng%3D%3D")?,
+            "https://www.instagram.com/p/CJBsZ11MYha/"
+        );
+        assert_eq!(
+            strip_share_ids(
+                "https://www.instagram.com/p/CJBsZ11MYha/?img_index=2&igshid=ffffffffffff&hl=en"
+            )?,
+            "https://www.instagram.com/p/CJBsZ11MYha/?img_index=2&hl=en"
+        );
+        assert_eq!(
+            strip_share_ids("https://www.instagram.com/p/CJBsZ11MYha/?hl=en")?,
+            "https://www.instagram.com/p/CJBsZ11MYha/?hl=en"
+        );
+        assert_eq!(
+            strip_share_ids("https://www.instagram.com/p/CJBsZ11MYha/?a=b%20c&igsh=x&d=e+f")?,
+            "https://www.instagram.com/p/CJBsZ11MYha/?a=b%20c&d=e+f"
+        );
+        assert_eq!(
+            strip_share_ids(
+                "https://l.instagram.com/?u=https%3A%2F%2Fwww.instagram.com%2Fp%2FCJBsZ11MYha%2F%3Figsh%3Dabc&e=ATM"
+            )?,
+            "https://l.instagram.com/?u=https%3A%2F%2Fwww.instagram.com%2Fp%2FCJBsZ11MYha%2F&e=ATM"
+        );
+        assert_eq!(
+            strip_share_ids(
+                "https://l.instagram.com/?u=https%3A%2F%2Fwww.instagram.com%2Fp%2FCJBsZ11MYha%2F&e=ATM"
+            )?,
+            "https://l.instagram.com/?u=https%3A%2F%2Fwww.instagram.com%2Fp%2FCJBsZ11MYha%2F&e=ATM"
+        );
+
+        Ok(())
+    }
+}
@@ -311,7 +311,7 @@ mod tests {
     #[test]
     fn test_is_private_shortcode() {
         for (_, shortcode) in DATA.iter() {
-            assert!(!is_private_shortcode(*shortcode));
+            assert!(!is_private_shortcode(shortcode));
             assert!(is_private_shortcode(
                 format!(
                     "{}{}",
@@ -330,10 +330,7 @@ mod tests {
     #[test]
     fn test_private_shortcode_to_public_shortcode() {
         for (_, shortcode) in DATA.iter() {
-            assert_eq!(
-                private_shortcode_to_public_shortcode(*shortcode),
-                *shortcode
-            );
+            assert_eq!(private_shortcode_to_public_shortcode(shortcode), *shortcode);
 
             assert_eq!(
                 private_shortcode_to_public_shortcode(